@@ -0,0 +1,206 @@
+use crate::{Kind, Target};
+use std::fmt;
+
+/// The difference between the targets in a manifest and the ones on disk.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Drift {
+    /// Files on disk without a target.
+    pub missing: Vec<Target>,
+    /// Targets whose file is gone.
+    pub stale: Vec<Target>,
+    /// Targets with the right path but the wrong name, as `(current, expected)`.
+    pub renamed: Vec<(Target, Target)>,
+    /// Targets with the right path and name but another kind or other
+    /// options, as `(current, expected)`.
+    pub changed: Vec<(Target, Target)>,
+    /// Same targets, but not in the order the generator writes them.
+    pub reordered: bool,
+    pub missing_members: Vec<String>,
+    pub stale_members: Vec<String>,
+    /// Generated files like the chapter index that need to be rewritten.
+    pub outdated_files: Vec<String>,
+}
+
+impl Drift {
+    pub fn new(
+        current: &[Target],
+        expected: &[Target],
+        current_members: &[String],
+        expected_members: &[String],
+    ) -> Self {
+        let mut drift = Drift::default();
+
+        for target in expected {
+            match current.iter().find(|other| other.path == target.path) {
+                Some(other) if other.name != target.name => {
+                    drift.renamed.push((other.clone(), target.clone()))
+                }
+                Some(other) if !other.same_manifest_entry(target) => {
+                    drift.changed.push((other.clone(), target.clone()))
+                }
+                Some(_) => {}
+                None => drift.missing.push(target.clone()),
+            }
+        }
+
+        for target in current {
+            if !expected.iter().any(|other| other.path == target.path) {
+                drift.stale.push(target.clone());
+            }
+        }
+
+        drift.missing_members = expected_members
+            .iter()
+            .filter(|member| !current_members.contains(member))
+            .cloned()
+            .collect();
+        drift.stale_members = current_members
+            .iter()
+            .filter(|member| !expected_members.contains(member))
+            .cloned()
+            .collect();
+
+        // Each kind is its own array in the manifest
+        let same_order = Kind::ALL.iter().all(|kind| {
+            let current = current.iter().filter(|target| target.kind == *kind);
+            let expected = expected.iter().filter(|target| target.kind == *kind);
+
+            current.clone().count() == expected.clone().count()
+                && current
+                    .zip(expected)
+                    .all(|(current, expected)| current.same_manifest_entry(expected))
+        });
+        drift.reordered = drift.is_empty() && (!same_order || current_members != expected_members);
+
+        drift
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.stale.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+            && !self.reordered
+            && self.missing_members.is_empty()
+            && self.stale_members.is_empty()
+            && self.outdated_files.is_empty()
+    }
+}
+
+/// One line per change, `+` for missing, `-` for stale and `~` for renamed or
+/// changed.
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for target in &self.missing {
+            writeln!(f, "+ [[{}]] {} ({})", target.kind, target.name, target.path)?;
+        }
+        for target in &self.stale {
+            writeln!(f, "- [[{}]] {} ({})", target.kind, target.name, target.path)?;
+        }
+        for (current, expected) in &self.renamed {
+            writeln!(
+                f,
+                "~ [[{}]] {} -> {} ({})",
+                expected.kind, current.name, expected.name, expected.path
+            )?;
+        }
+        for (current, expected) in &self.changed {
+            writeln!(
+                f,
+                "~ [[{}]] -> [[{}]] {} ({})",
+                current.kind, expected.kind, expected.name, expected.path
+            )?;
+        }
+        for member in &self.missing_members {
+            writeln!(f, "+ workspace member {member}")?;
+        }
+        for member in &self.stale_members {
+            writeln!(f, "- workspace member {member}")?;
+        }
+        if self.reordered {
+            writeln!(f, "~ targets are out of order")?;
+        }
+        for path in &self.outdated_files {
+            writeln!(f, "~ {path} is out of date")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bin(name: &str, path: &str) -> Target {
+        Target {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Target::default()
+        }
+    }
+
+    #[test]
+    fn finds_missing_stale_and_renamed() {
+        let current = vec![
+            bin("01_hello", "src/bin/01. Getting Started/hello_world.rs"),
+            bin(
+                "03_4_comments",
+                "src/bin/03. Common Programming Concepts/4_comments.rs",
+            ),
+        ];
+        let expected = vec![
+            bin(
+                "01_hello_world",
+                "src/bin/01. Getting Started/hello_world.rs",
+            ),
+            bin("02_main", "src/bin/02. Guessing Game/main.rs"),
+        ];
+        let drift = Drift::new(&current, &expected, &[], &[]);
+
+        assert_eq!(
+            "\
++ [[bin]] 02_main (src/bin/02. Guessing Game/main.rs)
+- [[bin]] 03_4_comments (src/bin/03. Common Programming Concepts/4_comments.rs)
+~ [[bin]] 01_hello -> 01_hello_world (src/bin/01. Getting Started/hello_world.rs)
+",
+            drift.to_string()
+        );
+    }
+
+    #[test]
+    fn finds_changed_kind() {
+        let current = vec![bin("11_1_tests", "src/bin/11. Testing/1_tests.rs")];
+        let expected = vec![Target {
+            kind: Kind::Test,
+            ..current[0].clone()
+        }];
+        let drift = Drift::new(&current, &expected, &[], &[]);
+
+        assert_eq!(
+            "~ [[bin]] -> [[test]] 11_1_tests (src/bin/11. Testing/1_tests.rs)\n",
+            drift.to_string()
+        );
+    }
+
+    #[test]
+    fn in_sync() {
+        let bins = vec![bin("02_main", "src/bin/02. Guessing Game/main.rs")];
+        let members = vec!["src/bin/12. Minigrep/minigrep".to_string()];
+
+        assert!(Drift::new(&bins, &bins, &members, &members).is_empty());
+    }
+
+    #[test]
+    fn reordered() {
+        let a = bin(
+            "01_hello_world",
+            "src/bin/01. Getting Started/hello_world.rs",
+        );
+        let b = bin("02_main", "src/bin/02. Guessing Game/main.rs");
+        let drift = Drift::new(&[b.clone(), a.clone()], &[a, b], &[], &[]);
+
+        assert!(drift.reordered);
+        assert!(!drift.is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Toml {
+        path: PathBuf,
+        source: toml_edit::TomlError,
+    },
+    Pattern(glob::PatternError),
+    Glob(glob::GlobError),
+    InvalidPath(PathBuf),
+    FrontMatter {
+        path: String,
+        message: String,
+    },
+    Conflicts(Vec<Conflict>),
+}
+
+/// A target name problem that cannot be fixed automatically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    Duplicate { name: String, paths: Vec<String> },
+    Empty { path: String },
+    Reserved { name: String, path: String },
+}
+
+/// A target name problem the generator fixed on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    Renamed {
+        path: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, .. } => write!(f, "Failed to access {}", path.display()),
+            Error::Toml { path, .. } => write!(f, "Failed to parse {}", path.display()),
+            Error::Pattern(e) => write!(f, "Invalid glob pattern: {e}"),
+            Error::Glob(e) => write!(f, "Failed to read {}", e.path().display()),
+            Error::InvalidPath(path) => write!(f, "Path is not valid UTF-8: {}", path.display()),
+            Error::FrontMatter { path, message } => write!(f, "{path}: {message}"),
+            Error::Conflicts(conflicts) => {
+                write!(f, "Invalid target names:")?;
+                for conflict in conflicts {
+                    write!(f, "\n  {conflict}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Toml { source, .. } => Some(source),
+            Error::Pattern(e) => Some(e),
+            Error::Glob(e) => Some(e),
+            Error::InvalidPath(_) | Error::FrontMatter { .. } | Error::Conflicts(_) => None,
+        }
+    }
+}
+
+impl From<glob::PatternError> for Error {
+    fn from(e: glob::PatternError) -> Self {
+        Error::Pattern(e)
+    }
+}
+
+impl From<glob::GlobError> for Error {
+    fn from(e: glob::GlobError) -> Self {
+        Error::Glob(e)
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Conflict::Duplicate { name, paths } => {
+                write!(f, "`{name}` is used by {}", paths.join(", "))
+            }
+            Conflict::Empty { path } => write!(f, "{path} has no usable characters in its name"),
+            Conflict::Reserved { name, path } => {
+                write!(f, "`{name}` from {path} is reserved by Cargo")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::Renamed { path, from, to } => {
+                write!(f, "Renamed `{from}` to `{to}` for {path}")
+            }
+        }
+    }
+}
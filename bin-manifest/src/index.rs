@@ -0,0 +1,397 @@
+use crate::{Kind, Target};
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table, TomlError};
+
+pub const TOC_START: &str = "<!-- chapters:start -->";
+pub const TOC_END: &str = "<!-- chapters:end -->";
+
+/// The table of contents encoded in `NN. Title/N_section_title.rs` paths.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Index {
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// `None` for folders without a number, like `Extras`.
+    pub number: Option<u32>,
+    pub title: String,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// `None` for files without a number, like `main.rs`.
+    pub order: Option<u32>,
+    pub title: String,
+    pub name: String,
+    pub path: String,
+    pub kind: Kind,
+    pub stdin: bool,
+}
+
+impl Section {
+    /// A short id like `4.3`, or `2` for a chapter with a single unnumbered
+    /// section.
+    pub fn id(&self, chapter: &Chapter) -> Option<String> {
+        match (chapter.number, self.order) {
+            (Some(number), Some(order)) => Some(format!("{number}.{order}")),
+            (Some(number), None) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The command that runs the section.
+    pub fn command(&self) -> String {
+        match self.kind {
+            Kind::Bin => format!("cargo run --bin {}", self.name),
+            Kind::Test => format!("cargo test --test {}", self.name),
+            Kind::Example => format!("cargo run --example {}", self.name),
+        }
+    }
+}
+
+impl Index {
+    /// Groups the targets by their folder, keeping the discovery order.
+    pub fn new(targets: &[Target]) -> Self {
+        let mut index = Index::default();
+
+        for target in targets {
+            let mut parts = target.path.rsplit('/');
+            let stem = parts.next().unwrap_or_default().trim_end_matches(".rs");
+            let folder = parts.next().unwrap_or_default();
+
+            let (number, chapter_title) = split_number(folder, ". ");
+            let (order, section_title) = split_number(stem, "_");
+
+            let section = Section {
+                order,
+                title: target
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| title_case(section_title)),
+                name: target.name.clone(),
+                path: target.path.clone(),
+                kind: target.kind,
+                stdin: target.stdin,
+            };
+
+            match index
+                .chapters
+                .iter_mut()
+                .find(|chapter| chapter.number == number && chapter.title == chapter_title)
+            {
+                Some(chapter) => chapter.sections.push(section),
+                None => index.chapters.push(Chapter {
+                    number,
+                    title: chapter_title.to_string(),
+                    sections: vec![section],
+                }),
+            }
+        }
+
+        index
+    }
+
+    /// Finds a section by its id (`4.3`) or target name.
+    pub fn find(&self, query: &str) -> Option<(&Chapter, &Section)> {
+        self.sections().find(|(chapter, section)| {
+            section.name == query || section.id(chapter).as_deref() == Some(query)
+        })
+    }
+
+    /// Every section with its chapter, in book order.
+    pub fn sections(&self) -> impl Iterator<Item = (&Chapter, &Section)> {
+        self.chapters.iter().flat_map(|chapter| {
+            chapter
+                .sections
+                .iter()
+                .map(move |section| (chapter, section))
+        })
+    }
+
+    /// A Markdown table with one row per section.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("| Id | Chapter | Section | Run |\n");
+        markdown.push_str("| --- | --- | --- | --- |\n");
+
+        for (chapter, section) in self.sections() {
+            let chapter_title = match chapter.number {
+                Some(number) => format!("{number}. {}", chapter.title),
+                None => chapter.title.clone(),
+            };
+
+            markdown.push_str(&format!(
+                "| {} | {} | [{}](<{}>) | `{}` |\n",
+                section.id(chapter).unwrap_or_default(),
+                chapter_title,
+                section.title,
+                section.path,
+                section.command()
+            ));
+        }
+
+        markdown
+    }
+
+    /// Replaces the part of `readme` between [`TOC_START`] and [`TOC_END`],
+    /// or appends a `Chapters` section with both markers.
+    pub fn update_readme(&self, readme: &str) -> String {
+        let toc = format!("{TOC_START}\n\n{}\n{TOC_END}", self.to_markdown());
+
+        match (readme.find(TOC_START), readme.find(TOC_END)) {
+            (Some(start), Some(end)) if start < end => format!(
+                "{}{toc}{}",
+                &readme[..start],
+                &readme[end + TOC_END.len()..]
+            ),
+            _ => format!("{}\n\n## Chapters\n\n{toc}\n", readme.trim_end()),
+        }
+    }
+}
+
+/// Written as one `[[chapter]]` table per chapter, each with its
+/// `[[chapter.section]]` tables.
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut doc = DocumentMut::new();
+        doc.decor_mut()
+            .set_prefix("# Generated by bin-manifest from src/bin, do not edit\n\n");
+
+        let mut chapters = ArrayOfTables::new();
+        for chapter in &self.chapters {
+            let mut table = Table::new();
+            if let Some(number) = chapter.number {
+                table["number"] = value(i64::from(number));
+            }
+            table["title"] = value(&chapter.title);
+
+            let mut sections = ArrayOfTables::new();
+            for section in &chapter.sections {
+                let mut row = Table::new();
+                if let Some(order) = section.order {
+                    row["order"] = value(i64::from(order));
+                }
+                row["title"] = value(&section.title);
+                row["name"] = value(&section.name);
+                row["path"] = value(&section.path);
+                row["kind"] = value(section.kind.key());
+                if section.stdin {
+                    row["stdin"] = value(true);
+                }
+                sections.push(row);
+            }
+            table["section"] = Item::ArrayOfTables(sections);
+
+            chapters.push(table);
+        }
+        doc["chapter"] = Item::ArrayOfTables(chapters);
+
+        write!(f, "{doc}")
+    }
+}
+
+impl FromStr for Index {
+    type Err = TomlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let doc = s.parse::<DocumentMut>()?;
+        let number = |table: &Table, key| {
+            table
+                .get(key)
+                .and_then(Item::as_integer)
+                .and_then(|number| u32::try_from(number).ok())
+        };
+        let text = |table: &Table, key| {
+            table
+                .get(key)
+                .and_then(Item::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let mut index = Index::default();
+        for table in doc
+            .get("chapter")
+            .and_then(Item::as_array_of_tables)
+            .into_iter()
+            .flatten()
+        {
+            let sections = table
+                .get("section")
+                .and_then(Item::as_array_of_tables)
+                .into_iter()
+                .flatten()
+                .map(|row| Section {
+                    order: number(row, "order"),
+                    title: text(row, "title"),
+                    name: text(row, "name"),
+                    path: text(row, "path"),
+                    kind: match row.get("kind").and_then(Item::as_str) {
+                        Some("test") => Kind::Test,
+                        Some("example") => Kind::Example,
+                        _ => Kind::Bin,
+                    },
+                    stdin: row.get("stdin").and_then(Item::as_bool).unwrap_or(false),
+                })
+                .collect();
+
+            index.chapters.push(Chapter {
+                number: number(table, "number"),
+                title: text(table, "title"),
+                sections,
+            });
+        }
+
+        Ok(index)
+    }
+}
+
+/// `"04. Ownership"` -> `(Some(4), "Ownership")`, `"Extras"` -> `(None, "Extras")`
+fn split_number<'a>(text: &'a str, separator: &str) -> (Option<u32>, &'a str) {
+    match text.split_once(separator) {
+        Some((number, rest)) => match number.parse() {
+            Ok(number) => (Some(number), rest),
+            Err(_) => (None, text),
+        },
+        None => (None, text),
+    }
+}
+
+/// Words that stay lowercase in titles unless they come first.
+const SMALL_WORDS: [&str; 15] = [
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "of", "on", "or", "the", "to", "with",
+];
+
+/// `"references_and_borrowing"` -> `"References and Borrowing"`
+fn title_case(text: &str) -> String {
+    text.split('_')
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(i, word)| {
+            if i > 0 && SMALL_WORDS.contains(&word) {
+                return word.to_string();
+            }
+
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(name: &str, path: &str) -> Target {
+        Target {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Target::default()
+        }
+    }
+
+    fn index() -> Index {
+        Index::new(&[
+            Target {
+                stdin: true,
+                title: Some("Guessing Game".to_string()),
+                ..target("02_main", "src/bin/02. Guessing Game/main.rs")
+            },
+            target(
+                "04_3_fixing_ownership_errors",
+                "src/bin/04. Ownership/3_fixing_ownership_errors.rs",
+            ),
+            Target {
+                kind: Kind::Test,
+                ..target("11_1_tests", "src/bin/11. Testing/1_tests.rs")
+            },
+            target("extras_snake_game", "src/bin/Extras/snake_game.rs"),
+        ])
+    }
+
+    #[test]
+    fn groups_sections_by_chapter() {
+        let index = index();
+
+        assert_eq!(4, index.chapters.len());
+        assert_eq!(Some(4), index.chapters[1].number);
+        assert_eq!("Ownership", index.chapters[1].title);
+        assert_eq!(
+            Section {
+                order: Some(3),
+                title: "Fixing Ownership Errors".to_string(),
+                name: "04_3_fixing_ownership_errors".to_string(),
+                path: "src/bin/04. Ownership/3_fixing_ownership_errors.rs".to_string(),
+                kind: Kind::Bin,
+                stdin: false,
+            },
+            index.chapters[1].sections[0]
+        );
+        assert_eq!(None, index.chapters[3].number);
+        assert_eq!("Snake Game", index.chapters[3].sections[0].title);
+    }
+
+    #[test]
+    fn titles() {
+        assert_eq!(
+            "Fixing Ownership Errors",
+            title_case("fixing_ownership_errors")
+        );
+        assert_eq!("Errors with Results", title_case("errors_with_results"));
+        assert_eq!("To Panic or Not", title_case("to_panic_or_not"));
+    }
+
+    #[test]
+    fn finds_sections() {
+        let index = index();
+
+        assert_eq!("Guessing Game", index.find("2").unwrap().1.title);
+        assert_eq!(
+            "Fixing Ownership Errors",
+            index.find("4.3").unwrap().1.title
+        );
+        assert_eq!(
+            "Snake Game",
+            index.find("extras_snake_game").unwrap().1.title
+        );
+        assert!(index.find("4.4").is_none());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let index = index();
+
+        assert_eq!(index, index.to_string().parse::<Index>().unwrap());
+    }
+
+    #[test]
+    fn writes_markdown() {
+        let readme = "# Book\n\n<!-- chapters:start -->\nold\n<!-- chapters:end -->\n\n## TODO\n";
+
+        assert_eq!(
+            "\
+# Book
+
+<!-- chapters:start -->
+
+| Id | Chapter | Section | Run |
+| --- | --- | --- | --- |
+| 2 | 2. Guessing Game | [Guessing Game](<src/bin/02. Guessing Game/main.rs>) | `cargo run --bin 02_main` |
+| 4.3 | 4. Ownership | [Fixing Ownership Errors](<src/bin/04. Ownership/3_fixing_ownership_errors.rs>) | `cargo run --bin 04_3_fixing_ownership_errors` |
+| 11.1 | 11. Testing | [Tests](<src/bin/11. Testing/1_tests.rs>) | `cargo test --test 11_1_tests` |
+|  | Extras | [Snake Game](<src/bin/Extras/snake_game.rs>) | `cargo run --bin extras_snake_game` |
+
+<!-- chapters:end -->
+
+## TODO
+",
+            index().update_readme(readme)
+        );
+    }
+}
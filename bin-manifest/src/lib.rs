@@ -0,0 +1,565 @@
+//! Generates the `[[bin]]` section of a `Cargo.toml` from a folder structure
+//! like `src/bin/NN. Title/file.rs`.
+//!
+//! A consumer's `build.rs` only needs a single call:
+//!
+//! ```no_run
+//! // build.rs
+//! bin_manifest::generate().expect("Failed to generate Cargo.toml");
+//! ```
+//!
+//! Use [`Generator`] to change the glob patterns, the manifest path or the
+//! naming scheme.
+
+mod drift;
+mod error;
+mod index;
+mod manifest;
+mod naming;
+mod target;
+
+pub use drift::Drift;
+pub use error::{Conflict, Error, Warning};
+pub use index::{Chapter, Index, Section};
+pub use manifest::Manifest;
+pub use naming::Naming;
+pub use target::{FrontMatter, Kind, Options, Target};
+
+use glob::glob;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PATTERN: &str = "src/bin/*/*.rs";
+pub const DEFAULT_MEMBER_PATTERN: &str = "src/bin/**/Cargo.toml";
+
+/// The targets found by [`Generator::discover`].
+#[derive(Debug, Default)]
+pub struct Discovery {
+    pub targets: Vec<Target>,
+    pub warnings: Vec<Warning>,
+}
+
+pub struct Generator {
+    root: PathBuf,
+    manifest_path: PathBuf,
+    patterns: Vec<String>,
+    member_patterns: Vec<String>,
+    naming: Naming,
+    index_path: Option<PathBuf>,
+    readme_path: Option<PathBuf>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator {
+            root: PathBuf::from("."),
+            manifest_path: PathBuf::from("Cargo.toml"),
+            patterns: vec![DEFAULT_PATTERN.to_string()],
+            member_patterns: vec![DEFAULT_MEMBER_PATTERN.to_string()],
+            naming: Naming::default(),
+            index_path: None,
+            readme_path: None,
+        }
+    }
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The directory the patterns and the manifest path are relative to.
+    pub fn root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
+
+    pub fn manifest_path(mut self, manifest_path: impl Into<PathBuf>) -> Self {
+        self.manifest_path = manifest_path.into();
+        self
+    }
+
+    /// Replaces the default `src/bin/*/*.rs` pattern.
+    pub fn patterns<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replaces the default `src/bin/**/Cargo.toml` pattern used to find
+    /// nested crates.
+    ///
+    /// Workspace members matching these patterns are owned by the generator,
+    /// any other member is left alone.
+    pub fn member_patterns<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.member_patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn naming(mut self, naming: Naming) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Also writes the chapter [`Index`] as TOML to this path.
+    pub fn index_path(mut self, index_path: impl Into<PathBuf>) -> Self {
+        self.index_path = Some(index_path.into());
+        self
+    }
+
+    /// Also keeps a Markdown table of contents up to date in this file, see
+    /// [`Index::update_readme`].
+    pub fn readme_path(mut self, readme_path: impl Into<PathBuf>) -> Self {
+        self.readme_path = Some(readme_path.into());
+        self
+    }
+
+    /// Finds every source file matching the patterns, in glob order.
+    ///
+    /// Files that belong to a nested crate are skipped, those are built as
+    /// workspace members instead. The [`FrontMatter`] of each file can change
+    /// its kind, name and options. Names are turned into valid target names,
+    /// duplicate or unusable names are returned as [`Error::Conflicts`].
+    pub fn discover(&self) -> Result<Discovery, Error> {
+        let members = self.discover_members()?;
+        let mut discovery = Discovery::default();
+        let mut conflicts = Vec::new();
+
+        for pattern in &self.patterns {
+            for path in self.glob(pattern)? {
+                if members
+                    .iter()
+                    .any(|member| Path::new(&path).starts_with(member))
+                {
+                    continue;
+                }
+
+                // The same file can match more than one pattern
+                if discovery.targets.iter().any(|target| target.path == path) {
+                    continue;
+                }
+
+                let front_matter = self.front_matter(&path)?;
+                let raw = front_matter
+                    .name
+                    .unwrap_or_else(|| self.naming.name(Path::new(&path)));
+                let name = naming::sanitize(&raw);
+
+                if name.is_empty() {
+                    conflicts.push(Conflict::Empty { path });
+                    continue;
+                }
+                if naming::RESERVED.contains(&name.as_str()) {
+                    conflicts.push(Conflict::Reserved { name, path });
+                    continue;
+                }
+                if name != raw {
+                    discovery.warnings.push(Warning::Renamed {
+                        path: path.clone(),
+                        from: raw,
+                        to: name.clone(),
+                    });
+                }
+
+                discovery.targets.push(Target {
+                    kind: front_matter.kind.unwrap_or_default(),
+                    name,
+                    path,
+                    options: front_matter.options,
+                    stdin: front_matter.stdin,
+                    title: front_matter.title,
+                });
+            }
+        }
+
+        for (i, target) in discovery.targets.iter().enumerate() {
+            let paths = discovery
+                .targets
+                .iter()
+                .filter(|other| other.name == target.name)
+                .map(|other| other.path.clone())
+                .collect::<Vec<_>>();
+
+            // Only report each name once, from its first file
+            let first = discovery
+                .targets
+                .iter()
+                .position(|other| other.name == target.name);
+            if paths.len() > 1 && first == Some(i) {
+                conflicts.push(Conflict::Duplicate {
+                    name: target.name.clone(),
+                    paths,
+                });
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(Error::Conflicts(conflicts));
+        }
+
+        Ok(discovery)
+    }
+
+    /// Finds every directory with its own `Cargo.toml`, in glob order.
+    pub fn discover_members(&self) -> Result<Vec<String>, Error> {
+        let mut members = Vec::new();
+
+        for pattern in &self.member_patterns {
+            for path in self.glob(pattern)? {
+                // Packaged copies of a crate also have a Cargo.toml
+                if path.split('/').any(|part| part == "target") {
+                    continue;
+                }
+
+                if let Some((member, _)) = path.rsplit_once('/') {
+                    members.push(member.to_string());
+                }
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Rewrites the manifest so its targets and workspace members match the
+    /// discovered files, along with the index and the readme when they are set.
+    ///
+    /// Files are only written when something differs, returns whether any was.
+    pub fn generate(&self) -> Result<bool, Error> {
+        let discovery = self.discover()?;
+        self.write(&discovery.targets)
+    }
+
+    /// Compares the manifest with the discovered files without writing
+    /// anything.
+    pub fn check(&self) -> Result<Drift, Error> {
+        let discovery = self.discover()?;
+        let manifest = self.read_manifest()?;
+
+        let mut drift = self.drift(&manifest, &discovery.targets)?;
+        for (path, contents) in self.generated_files(&discovery.targets)? {
+            if fs::read_to_string(self.root.join(&path)).ok() != Some(contents) {
+                drift.outdated_files.push(path.display().to_string());
+            }
+        }
+
+        Ok(drift)
+    }
+
+    /// The chapter index of the discovered files.
+    pub fn index(&self) -> Result<Index, Error> {
+        Ok(Index::new(&self.discover()?.targets))
+    }
+
+    fn write(&self, targets: &[Target]) -> Result<bool, Error> {
+        let mut written = self.write_manifest(targets)?;
+
+        for (path, contents) in self.generated_files(targets)? {
+            let path = self.root.join(path);
+
+            if fs::read_to_string(&path).ok() != Some(contents.clone()) {
+                fs::write(&path, contents).map_err(|source| Error::Io { path, source })?;
+                written = true;
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// The index and readme contents for `targets`, for the paths that are set.
+    fn generated_files(&self, targets: &[Target]) -> Result<Vec<(PathBuf, String)>, Error> {
+        let index = Index::new(targets);
+        let mut files = Vec::new();
+
+        if let Some(path) = &self.index_path {
+            files.push((path.clone(), index.to_string()));
+        }
+
+        if let Some(path) = &self.readme_path {
+            let full_path = self.root.join(path);
+            let readme = fs::read_to_string(&full_path).map_err(|source| Error::Io {
+                path: full_path,
+                source,
+            })?;
+            files.push((path.clone(), index.update_readme(&readme)));
+        }
+
+        Ok(files)
+    }
+
+    fn write_manifest(&self, targets: &[Target]) -> Result<bool, Error> {
+        let mut manifest = self.read_manifest()?;
+
+        if self.drift(&manifest, targets)?.is_empty() {
+            return Ok(false);
+        }
+
+        let members = self.discover_members()?;
+        manifest.set_targets(targets, |path| self.owns_target(path));
+        manifest.set_members(&members, |member| self.owns_member(member));
+
+        let manifest_path = self.root.join(&self.manifest_path);
+        fs::write(&manifest_path, manifest.to_string()).map_err(|source| Error::Io {
+            path: manifest_path,
+            source,
+        })?;
+
+        Ok(true)
+    }
+
+    fn drift(&self, manifest: &Manifest, targets: &[Target]) -> Result<Drift, Error> {
+        let members = self.discover_members()?;
+        let owned_members = manifest
+            .members()
+            .into_iter()
+            .filter(|member| self.owns_member(member))
+            .collect::<Vec<_>>();
+
+        let owned_targets = manifest
+            .targets()
+            .into_iter()
+            .filter(|target| self.owns_target(&target.path))
+            .collect::<Vec<_>>();
+
+        Ok(Drift::new(
+            &owned_targets,
+            targets,
+            &owned_members,
+            &members,
+        ))
+    }
+
+    fn read_manifest(&self) -> Result<Manifest, Error> {
+        let manifest_path = self.root.join(&self.manifest_path);
+
+        let original = fs::read_to_string(&manifest_path).map_err(|source| Error::Io {
+            path: manifest_path.clone(),
+            source,
+        })?;
+
+        original.parse::<Manifest>().map_err(|source| Error::Toml {
+            path: manifest_path,
+            source,
+        })
+    }
+
+    /// The paths a build script has to watch: the generated files and the
+    /// directory each pattern starts from.
+    pub fn watched_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.root.join(&self.manifest_path)];
+        for path in self.index_path.iter().chain(&self.readme_path) {
+            paths.push(self.root.join(path));
+        }
+
+        for pattern in self.patterns.iter().chain(&self.member_patterns) {
+            let base = pattern
+                .split('/')
+                .take_while(|part| !part.contains(['*', '?', '[']))
+                .collect::<Vec<_>>()
+                .join("/");
+            let path = self.root.join(base);
+
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        paths
+    }
+
+    /// Prints the `cargo:rerun-if-changed` lines and runs [`Generator::generate`].
+    ///
+    /// Meant to be called from `build.rs`, so the script only reruns when the
+    /// manifest or the watched directories change. Renamed targets are
+    /// reported as `cargo:warning`, conflicts fail the build before anything
+    /// is written.
+    pub fn build_script(&self) -> Result<(), Error> {
+        for path in self.watched_paths() {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+
+        let discovery = match self.discover() {
+            Ok(discovery) => discovery,
+            Err(Error::Conflicts(conflicts)) => {
+                for conflict in &conflicts {
+                    println!("cargo:warning={conflict}");
+                }
+                return Err(Error::Conflicts(conflicts));
+            }
+            Err(e) => return Err(e),
+        };
+
+        for warning in &discovery.warnings {
+            println!("cargo:warning={warning}");
+        }
+
+        self.write(&discovery.targets)?;
+        Ok(())
+    }
+
+    fn front_matter(&self, path: &str) -> Result<FrontMatter, Error> {
+        let full_path = self.root.join(path);
+        let source = fs::read_to_string(&full_path).map_err(|source| Error::Io {
+            path: full_path,
+            source,
+        })?;
+
+        FrontMatter::parse(&source).map_err(|message| Error::FrontMatter {
+            path: path.to_string(),
+            message,
+        })
+    }
+
+    /// Targets matching the patterns are owned by the generator, any other
+    /// target is left alone.
+    fn owns_target(&self, path: &str) -> bool {
+        matches_any(&self.patterns, path)
+    }
+
+    fn owns_member(&self, member: &str) -> bool {
+        matches_any(&self.member_patterns, &format!("{member}/Cargo.toml"))
+    }
+
+    /// Paths matching `pattern`, relative to the root and with `/` separators.
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, Error> {
+        let pattern = self.root.join(pattern);
+        let pattern = pattern
+            .to_str()
+            .ok_or(Error::InvalidPath(pattern.clone()))?;
+
+        let mut paths = Vec::new();
+        for entry in glob(pattern)? {
+            let path = entry?;
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+
+            // Get the file path as a string
+            let relative = relative
+                .to_str()
+                .ok_or_else(|| Error::InvalidPath(path.clone()))?
+                .replace('\\', "/");
+
+            paths.push(relative);
+        }
+
+        Ok(paths)
+    }
+}
+
+fn matches_any(patterns: &[String], path: &str) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..glob::MatchOptions::new()
+    };
+
+    patterns.iter().any(|pattern| {
+        glob::Pattern::new(pattern)
+            .map(|pattern| pattern.matches_with(path, options))
+            .unwrap_or(false)
+    })
+}
+
+/// Runs the [`Generator`] with its defaults from the current directory, see
+/// [`Generator::build_script`].
+pub fn generate() -> Result<(), Error> {
+    Generator::default().build_script()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    /// A throwaway book with one lesson and a nested crate.
+    fn book(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("bin-manifest-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        fs::create_dir_all(root.join("src/bin/01. Getting Started")).unwrap();
+        fs::create_dir_all(root.join("src/bin/12. Minigrep/minigrep/src")).unwrap();
+        fs::write(
+            root.join("src/bin/01. Getting Started/hello_world.rs"),
+            "fn main() {}",
+        )
+        .unwrap();
+        fs::write(root.join("src/bin/12. Minigrep/minigrep/Cargo.toml"), "").unwrap();
+        fs::write(root.join("src/bin/12. Minigrep/minigrep/src/main.rs"), "").unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"book\"\n").unwrap();
+
+        root
+    }
+
+    #[test]
+    fn generates_once() {
+        let root = book("generates_once");
+        let generator = Generator::new().root(&root).patterns(["src/bin/**/*.rs"]);
+
+        assert!(generator.generate().unwrap());
+        assert!(!generator.generate().unwrap());
+
+        let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        let manifest = manifest.parse::<Manifest>().unwrap();
+        assert_eq!(
+            vec![Target {
+                name: "01_hello_world".to_string(),
+                path: "src/bin/01. Getting Started/hello_world.rs".to_string(),
+                ..Target::default()
+            }],
+            manifest.targets()
+        );
+        assert_eq!(vec!["src/bin/12. Minigrep/minigrep"], manifest.members());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reports_conflicts() {
+        let root = book("reports_conflicts");
+        fs::write(root.join("src/bin/01. Getting Started/hello world.rs"), "").unwrap();
+        fs::create_dir_all(root.join("src/bin/Ő")).unwrap();
+        fs::write(root.join("src/bin/Ő/ű.rs"), "").unwrap();
+
+        let generator = Generator::new().root(&root);
+        match generator.discover() {
+            Err(Error::Conflicts(conflicts)) => assert_eq!(
+                vec![
+                    Conflict::Empty {
+                        path: "src/bin/Ő/ű.rs".to_string()
+                    },
+                    Conflict::Duplicate {
+                        name: "01_hello_world".to_string(),
+                        paths: vec![
+                            "src/bin/01. Getting Started/hello world.rs".to_string(),
+                            "src/bin/01. Getting Started/hello_world.rs".to_string(),
+                        ],
+                    },
+                ],
+                conflicts
+            ),
+            other => panic!("Expected conflicts, got {other:?}"),
+        }
+
+        let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert_eq!("[package]\nname = \"book\"\n", manifest);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn watched_paths() {
+        let generator = Generator::new().root("book");
+
+        assert_eq!(
+            vec![
+                PathBuf::from("book/Cargo.toml"),
+                PathBuf::from("book/src/bin")
+            ],
+            generator.watched_paths()
+        );
+    }
+}
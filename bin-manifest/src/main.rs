@@ -0,0 +1,82 @@
+use bin_manifest::{Error, Generator};
+use std::env;
+use std::process;
+
+const USAGE: &str = "\
+Usage: bin-manifest check [options]
+
+Reports the differences between Cargo.toml and the files on disk without
+writing anything. Exits with 1 when they differ and 2 on errors.
+
+Options:
+  --root <dir>            Directory the patterns are relative to [default: .]
+  --manifest-path <file>  Manifest to check [default: Cargo.toml]
+  --index <file>          Also check the generated chapter index
+  --readme <file>         Also check the table of contents in this readme
+  -h, --help              Prints help information";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let generator = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{err}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    match generator.check() {
+        Ok(drift) if drift.is_empty() => println!("Everything is up to date"),
+        Ok(drift) => {
+            println!("Out of date:");
+            print!("{drift}");
+            process::exit(1);
+        }
+        Err(Error::Conflicts(conflicts)) => {
+            for conflict in conflicts {
+                eprintln!("error: {conflict}");
+            }
+            process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            process::exit(2);
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Generator, String> {
+    let mut generator = Generator::new();
+    let mut command = None;
+    let mut args = args.iter().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                process::exit(0);
+            }
+            "--root" => {
+                let root = args.next().ok_or("Missing value for --root")?;
+                generator = generator.root(root);
+            }
+            "--manifest-path" => {
+                let path = args.next().ok_or("Missing value for --manifest-path")?;
+                generator = generator.manifest_path(path);
+            }
+            "--index" => {
+                let path = args.next().ok_or("Missing value for --index")?;
+                generator = generator.index_path(path);
+            }
+            "--readme" => {
+                let path = args.next().ok_or("Missing value for --readme")?;
+                generator = generator.readme_path(path);
+            }
+            "check" if command.is_none() => command = Some(arg),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+    }
+
+    match command {
+        Some(_) => Ok(generator),
+        None => Err("Missing command".to_string()),
+    }
+}
@@ -0,0 +1,503 @@
+use crate::target::{Kind, Options, Target};
+use std::fmt;
+use std::str::FromStr;
+use toml_edit::{table, value, Array, ArrayOfTables, DocumentMut, Item, Table, TomlError};
+
+/// A `Cargo.toml` that can be edited without losing its formatting.
+pub struct Manifest {
+    doc: DocumentMut,
+}
+
+impl FromStr for Manifest {
+    type Err = TomlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Manifest { doc: s.parse()? })
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.doc)
+    }
+}
+
+impl Manifest {
+    /// The `[[bin]]`, `[[test]]` and `[[example]]` tables that have both a
+    /// name and a path.
+    pub fn targets(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+
+        for kind in Kind::ALL {
+            let tables = self
+                .doc
+                .get(kind.key())
+                .and_then(Item::as_array_of_tables)
+                .into_iter()
+                .flatten();
+
+            for table in tables {
+                if let Some(target) = read_target(kind, table) {
+                    targets.push(target);
+                }
+            }
+        }
+
+        targets
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.doc
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(Item::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|member| member.as_str().map(String::from))
+            .collect()
+    }
+
+    /// Replaces the targets for which `is_owned` returns true with `targets`.
+    ///
+    /// Every other table, comment and the ordering of the file are kept as
+    /// written. Existing tables with a matching path are reused, so their
+    /// comments and extra keys survive too.
+    pub fn set_targets(&mut self, targets: &[Target], is_owned: impl Fn(&str) -> bool) {
+        for kind in Kind::ALL {
+            let mut existing = match self.doc.remove(kind.key()) {
+                Some(Item::ArrayOfTables(tables)) => tables.into_iter().collect(),
+                _ => Vec::new(),
+            };
+
+            let owned = |table: &Table| {
+                table
+                    .get("path")
+                    .and_then(Item::as_str)
+                    .is_some_and(&is_owned)
+            };
+
+            // Generated targets go where the first one was, the tables that are
+            // not owned keep their own place
+            let position = existing
+                .iter()
+                .find(|table| owned(table))
+                .or(existing.first())
+                .and_then(Table::position);
+
+            let mut tables = ArrayOfTables::new();
+            existing.retain(|table| {
+                if !owned(table) {
+                    tables.push(table.clone());
+                }
+                owned(table)
+            });
+
+            for target in targets.iter().filter(|target| target.kind == kind) {
+                let reused = existing.iter().position(|table| {
+                    table.get("path").and_then(Item::as_str) == Some(&target.path)
+                });
+
+                let mut table = match reused {
+                    Some(index) => existing.remove(index),
+                    None => {
+                        let mut table = Table::new();
+                        table.decor_mut().set_prefix("\n");
+                        table
+                    }
+                };
+
+                write_target(&mut table, target);
+                table.set_position(position);
+
+                tables.push(table);
+            }
+
+            if !tables.is_empty() {
+                self.doc.insert(kind.key(), Item::ArrayOfTables(tables));
+            }
+        }
+    }
+
+    /// Replaces the `workspace.members` entries for which `is_owned` returns
+    /// true with `members`.
+    ///
+    /// Members that are not owned keep their place, new ones are appended.
+    pub fn set_members(&mut self, members: &[String], is_owned: impl Fn(&str) -> bool) {
+        let current = self
+            .doc
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(Item::as_array);
+
+        if current.is_none() && members.is_empty() {
+            return;
+        }
+
+        let mut updated = Array::new();
+        for member in current.into_iter().flatten() {
+            if !member.as_str().is_some_and(&is_owned) {
+                updated.push_formatted(member.clone());
+            }
+        }
+        for member in members {
+            updated.push(member.as_str());
+        }
+
+        let workspace = self.doc.entry("workspace").or_insert_with(table);
+        workspace["members"] = value(updated);
+    }
+}
+
+/// The keys the generator owns, in the order it writes them.
+const KEYS: [&str; 7] = [
+    "name",
+    "path",
+    "required-features",
+    "test",
+    "bench",
+    "doc",
+    "harness",
+];
+
+fn read_target(kind: Kind, table: &Table) -> Option<Target> {
+    let flag = |key| table.get(key).and_then(Item::as_bool);
+
+    let required_features = table
+        .get("required-features")
+        .and_then(Item::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|feature| feature.as_str().map(String::from))
+        .collect();
+
+    Some(Target {
+        kind,
+        name: table.get("name")?.as_str()?.to_string(),
+        path: table.get("path")?.as_str()?.to_string(),
+        options: Options {
+            required_features,
+            test: flag("test"),
+            bench: flag("bench"),
+            doc: flag("doc"),
+            harness: flag("harness"),
+        },
+        ..Target::default()
+    })
+}
+
+/// Updates the keys that differ, so untouched values keep their formatting.
+fn write_target(table: &mut Table, target: &Target) {
+    let options = &target.options;
+    let features = (!options.required_features.is_empty())
+        .then(|| value(options.required_features.iter().collect::<Array>()));
+
+    let values = [
+        Some(value(&target.name)),
+        Some(value(&target.path)),
+        features,
+        options.test.map(value),
+        options.bench.map(value),
+        options.doc.map(value),
+        options.harness.map(value),
+    ];
+
+    let mut inserted = false;
+    for (key, item) in KEYS.into_iter().zip(values) {
+        match item {
+            Some(item) => match table.get(key) {
+                Some(current) if same_value(current, &item) => {}
+                current => {
+                    inserted |= current.is_none();
+                    table[key] = item;
+                }
+            },
+            None => {
+                table.remove(key);
+            }
+        }
+    }
+
+    if inserted {
+        table.sort_values_by(|a, _, b, _| key_order(a.get()).cmp(&key_order(b.get())));
+    }
+}
+
+/// Compares two values without their whitespace and comments.
+fn same_value(a: &Item, b: &Item) -> bool {
+    let plain = |item: &Item| {
+        item.as_value().map(|value| {
+            let mut value = value.clone();
+            value.decor_mut().clear();
+            value.to_string()
+        })
+    };
+
+    plain(a).is_some() && plain(a) == plain(b)
+}
+
+fn key_order(key: &str) -> usize {
+    KEYS.iter()
+        .position(|known| *known == key)
+        .unwrap_or(KEYS.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replace_bins(original: &str, bins: &[Target]) -> String {
+        let mut manifest = original.parse::<Manifest>().unwrap();
+        manifest.set_targets(bins, |path| path.starts_with("src/bin/"));
+        manifest.to_string()
+    }
+
+    fn bin(name: &str, path: &str) -> Target {
+        Target {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Target::default()
+        }
+    }
+
+    #[test]
+    fn replaces_bin_section() {
+        let original = "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"old\"
+path = \"src/bin/old.rs\"
+";
+        let bins = vec![bin(
+            "01_hello_world",
+            "src/bin/01. Getting Started/hello_world.rs",
+        )];
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"01_hello_world\"
+path = \"src/bin/01. Getting Started/hello_world.rs\"
+",
+            replace_bins(original, &bins)
+        );
+    }
+
+    #[test]
+    fn keeps_tables_after_bins() {
+        let original = "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"01_hello_world\"
+path = \"src/bin/01. Getting Started/hello_world.rs\"
+
+# Kept on purpose
+[features]
+interactive = []
+
+[[example]]
+name = \"demo\"
+path = \"examples/demo.rs\"
+
+[profile.release]
+lto = true
+";
+        let bins = vec![
+            bin(
+                "01_hello_world",
+                "src/bin/01. Getting Started/hello_world.rs",
+            ),
+            bin("02_main", "src/bin/02. Guessing Game/main.rs"),
+        ];
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"01_hello_world\"
+path = \"src/bin/01. Getting Started/hello_world.rs\"
+
+[[bin]]
+name = \"02_main\"
+path = \"src/bin/02. Guessing Game/main.rs\"
+
+# Kept on purpose
+[features]
+interactive = []
+
+[[example]]
+name = \"demo\"
+path = \"examples/demo.rs\"
+
+[profile.release]
+lto = true
+",
+            replace_bins(original, &bins)
+        );
+    }
+
+    #[test]
+    fn keeps_unowned_bins_in_place() {
+        let original = "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"01_a\"
+path = \"src/bin/01. A/a.rs\"
+
+[features]
+interactive = []
+
+# my tool
+[[bin]]
+name = \"tool\"
+path = \"tools/tool.rs\"
+";
+        let bins = vec![
+            bin("01_a", "src/bin/01. A/a.rs"),
+            bin("02_b", "src/bin/02. B/b.rs"),
+        ];
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"01_a\"
+path = \"src/bin/01. A/a.rs\"
+
+[[bin]]
+name = \"02_b\"
+path = \"src/bin/02. B/b.rs\"
+
+[features]
+interactive = []
+
+# my tool
+[[bin]]
+name = \"tool\"
+path = \"tools/tool.rs\"
+",
+            replace_bins(original, &bins)
+        );
+    }
+
+    #[test]
+    fn keeps_comments_on_reused_bins() {
+        let original = "\
+[package]
+name = \"book\"
+
+# The first lesson
+[[bin]]
+name = \"hello\"
+path = \"src/bin/01. Getting Started/hello_world.rs\" # renamed below
+";
+        let bins = vec![bin(
+            "01_hello_world",
+            "src/bin/01. Getting Started/hello_world.rs",
+        )];
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+# The first lesson
+[[bin]]
+name = \"01_hello_world\"
+path = \"src/bin/01. Getting Started/hello_world.rs\" # renamed below
+",
+            replace_bins(original, &bins)
+        );
+    }
+
+    #[test]
+    fn writes_test_targets() {
+        let original = "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"learn\"
+path = \"src/learn.rs\"
+
+[[bin]]
+name = \"11_1_tests\"
+path = \"src/bin/11. Testing/1_tests.rs\"
+";
+        let targets = vec![Target {
+            kind: Kind::Test,
+            options: Options {
+                required_features: vec!["slow".to_string()],
+                harness: Some(true),
+                ..Options::default()
+            },
+            ..bin("11_1_tests", "src/bin/11. Testing/1_tests.rs")
+        }];
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+[[bin]]
+name = \"learn\"
+path = \"src/learn.rs\"
+
+[[test]]
+name = \"11_1_tests\"
+path = \"src/bin/11. Testing/1_tests.rs\"
+required-features = [\"slow\"]
+harness = true
+",
+            replace_bins(original, &targets)
+        );
+
+        let manifest = replace_bins(original, &targets)
+            .parse::<Manifest>()
+            .unwrap();
+        assert_eq!(
+            vec![bin("learn", "src/learn.rs"), targets[0].clone()],
+            manifest.targets()
+        );
+    }
+
+    #[test]
+    fn registers_nested_crates() {
+        let original = "\
+[package]
+name = \"book\"
+
+[workspace]
+members = [\"bin-manifest\", \"src/bin/12. Minigrep/minigrep\", \"src/bin/old\"]
+";
+        let mut manifest = original.parse::<Manifest>().unwrap();
+        let members = vec![
+            "src/bin/07. Packages, Crates and Modules/restaurant".to_string(),
+            "src/bin/12. Minigrep/minigrep".to_string(),
+        ];
+        manifest.set_members(&members, |member| member.starts_with("src/bin/"));
+
+        assert_eq!(
+            "\
+[package]
+name = \"book\"
+
+[workspace]
+members = [\"bin-manifest\", \"src/bin/07. Packages, Crates and Modules/restaurant\", \"src/bin/12. Minigrep/minigrep\"]
+",
+            manifest.to_string()
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+/// How a source file is turned into a target name.
+///
+/// `path` is always relative to the generator root and uses `/` as separator.
+#[derive(Clone, Copy, Default)]
+pub enum Naming {
+    /// `src/bin/04. Ownership/4_slice_type.rs` -> `04_4_slice_type`
+    ///
+    /// The folder name is cut at the first `.`, so a folder without a number
+    /// like `Extras` gives `extras_snake_game`.
+    #[default]
+    FolderNumber,
+    /// `src/bin/04. Ownership/4_slice_type.rs` -> `4_slice_type`
+    FileStem,
+    Custom(fn(&Path) -> String),
+}
+
+impl Naming {
+    pub fn name(&self, path: &Path) -> String {
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+
+        match self {
+            Naming::FolderNumber => {
+                // Get just the number from the folder name
+                let folder = path
+                    .parent()
+                    .and_then(|parent| parent.file_name())
+                    .and_then(|folder| folder.to_str())
+                    .unwrap_or_default();
+                let number = folder.split('.').next().unwrap_or_default();
+
+                format!("{}_{}", number.to_lowercase(), stem)
+            }
+            Naming::FileStem => stem.to_string(),
+            Naming::Custom(name) => name(path),
+        }
+    }
+}
+
+/// Names Cargo uses for its own directories in `target/`.
+pub const RESERVED: [&str; 4] = ["build", "deps", "examples", "incremental"];
+
+/// Turns `name` into a valid Cargo target name.
+///
+/// Anything other than ASCII letters, digits, `-` and `_` becomes `_`, runs of
+/// `_` are collapsed and trimmed from both ends.
+pub fn sanitize(name: &str) -> String {
+    let mut sanitized = String::new();
+
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '-' {
+            c
+        } else {
+            '_'
+        };
+
+        if c == '_' && (sanitized.is_empty() || sanitized.ends_with('_')) {
+            continue;
+        }
+        sanitized.push(c);
+    }
+
+    sanitized.trim_end_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_number() {
+        let path = Path::new("src/bin/04. Ownership/4_slice_type.rs");
+        assert_eq!("04_4_slice_type", Naming::FolderNumber.name(path));
+
+        let path = Path::new("src/bin/Extras/snake_game.rs");
+        assert_eq!("extras_snake_game", Naming::FolderNumber.name(path));
+    }
+
+    #[test]
+    fn custom() {
+        fn upper(path: &Path) -> String {
+            path.file_stem().unwrap().to_str().unwrap().to_uppercase()
+        }
+
+        let path = Path::new("src/bin/01. Getting Started/hello_world.rs");
+        assert_eq!("HELLO_WORLD", Naming::Custom(upper).name(path));
+    }
+
+    #[test]
+    fn sanitizes_names() {
+        assert_eq!("01_hello_world", sanitize("01_hello_world"));
+        assert_eq!("extras_snake_game", sanitize("extras_snake game"));
+        assert_eq!("10_k_sz_n_s", sanitize("10_köszönés"));
+        assert_eq!("a-b_c", sanitize("__a-b.c__"));
+        assert_eq!("n", sanitize("ünő"));
+        assert_eq!("", sanitize("ő_ű"));
+    }
+}
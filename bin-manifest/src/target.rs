@@ -0,0 +1,218 @@
+use std::fmt;
+use toml_edit::{DocumentMut, Item};
+
+/// Which array of tables a target is written to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Kind {
+    #[default]
+    Bin,
+    Test,
+    Example,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 3] = [Kind::Bin, Kind::Test, Kind::Example];
+
+    /// The manifest key, `[[bin]]` is stored under `bin`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Kind::Bin => "bin",
+            Kind::Test => "test",
+            Kind::Example => "example",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+/// The optional Cargo target fields the generator manages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options {
+    pub required_features: Vec<String>,
+    pub test: Option<bool>,
+    pub bench: Option<bool>,
+    pub doc: Option<bool>,
+    pub harness: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Target {
+    pub kind: Kind,
+    pub name: String,
+    pub path: String,
+    pub options: Options,
+    /// The lesson reads from stdin. Only used by tools, it is not written to
+    /// the manifest.
+    pub stdin: bool,
+    /// Overrides the section title in the chapter index.
+    pub title: Option<String>,
+}
+
+impl Target {
+    /// Compares only what ends up in the manifest.
+    pub fn same_manifest_entry(&self, other: &Target) -> bool {
+        self.kind == other.kind
+            && self.name == other.name
+            && self.path == other.path
+            && self.options == other.options
+    }
+}
+
+/// Settings read from the `//! key = value` lines at the top of a source file.
+///
+/// ```text
+/// //! target = "test"
+/// //! name = "guessing_game"
+/// //! required-features = ["interactive"]
+/// //! stdin = true
+/// //! title = "Guessing Game"
+/// ```
+///
+/// Values use TOML syntax. Other `//!` lines are regular doc comments and are
+/// ignored, the front matter ends at the first line that is not a `//!` comment.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    pub kind: Option<Kind>,
+    pub name: Option<String>,
+    pub options: Options,
+    pub stdin: bool,
+    pub title: Option<String>,
+}
+
+const KEYS: [&str; 9] = [
+    "target",
+    "name",
+    "required-features",
+    "test",
+    "bench",
+    "doc",
+    "harness",
+    "stdin",
+    "title",
+];
+
+impl FrontMatter {
+    pub fn parse(source: &str) -> Result<FrontMatter, String> {
+        let mut front_matter = FrontMatter::default();
+
+        for line in source.lines() {
+            let Some(line) = line.trim_start().strip_prefix("//!") else {
+                break;
+            };
+
+            let Some((key, _)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            if !KEYS.contains(&key) {
+                continue;
+            }
+
+            let doc = line
+                .parse::<DocumentMut>()
+                .map_err(|e| format!("Invalid value for `{key}`: {}", e.message()))?;
+            front_matter.set(key, &doc[key])?;
+        }
+
+        Ok(front_matter)
+    }
+
+    fn set(&mut self, key: &str, value: &Item) -> Result<(), String> {
+        let expected = |kind: &str| format!("Expected {kind} for `{key}`");
+
+        match key {
+            "target" => {
+                let kind = match value.as_str() {
+                    Some("bin") => Kind::Bin,
+                    Some("test") => Kind::Test,
+                    Some("example") => Kind::Example,
+                    _ => return Err(expected("\"bin\", \"test\" or \"example\"")),
+                };
+                self.kind = Some(kind);
+            }
+            "name" | "title" => {
+                let text = value.as_str().ok_or_else(|| expected("a string"))?;
+                match key {
+                    "name" => self.name = Some(text.to_string()),
+                    _ => self.title = Some(text.to_string()),
+                }
+            }
+            "required-features" => {
+                let features = value
+                    .as_array()
+                    .and_then(|features| {
+                        features
+                            .iter()
+                            .map(|feature| feature.as_str().map(String::from))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .ok_or_else(|| expected("an array of strings"))?;
+                self.options.required_features = features;
+            }
+            _ => {
+                let flag = value.as_bool().ok_or_else(|| expected("a boolean"))?;
+                match key {
+                    "test" => self.options.test = Some(flag),
+                    "bench" => self.options.bench = Some(flag),
+                    "doc" => self.options.doc = Some(flag),
+                    "harness" => self.options.harness = Some(flag),
+                    _ => self.stdin = flag,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_front_matter() {
+        let source = "\
+//! Shows how tests are organized.
+//! target = \"test\"
+//! name = \"organization\"
+//! required-features = [\"slow\"]
+//! harness = true
+//! stdin = true
+//! title = \"Test Organization\"
+
+//! target = \"example\"
+fn main() {}
+";
+
+        assert_eq!(
+            FrontMatter {
+                kind: Some(Kind::Test),
+                name: Some("organization".to_string()),
+                options: Options {
+                    required_features: vec!["slow".to_string()],
+                    harness: Some(true),
+                    ..Options::default()
+                },
+                stdin: true,
+                title: Some("Test Organization".to_string()),
+            },
+            FrontMatter::parse(source).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(
+            Err("Expected \"bin\", \"test\" or \"example\" for `target`".to_string()),
+            FrontMatter::parse("//! target = \"lib\"")
+        );
+        assert_eq!(
+            Err("Expected a boolean for `stdin`".to_string()),
+            FrontMatter::parse("//! stdin = \"yes\"")
+        );
+    }
+}
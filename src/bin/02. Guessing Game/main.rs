@@ -1,3 +1,6 @@
+//! stdin = true
+//! title = "Guessing Game"
+
 use rand::Rng;
 use std::cmp::Ordering;
 use std::io;
@@ -5,7 +8,7 @@ use std::io;
 fn main() {
     println!("-- [ Guessing Game ] --");
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+    let secret_number = learning_rust::rng().gen_range(1..=100);
 
     loop {
         println!("Please input your guess.");
@@ -1,3 +1,5 @@
+//! stdin = true
+
 use std::io;
 
 fn main() {
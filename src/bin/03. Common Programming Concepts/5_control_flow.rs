@@ -1,3 +1,5 @@
+#![allow(clippy::never_loop)]
+
 fn main() {
     // if Expressions
 
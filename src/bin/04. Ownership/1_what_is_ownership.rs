@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 fn read(y: bool) {
     if y {
         println!("y is true");
@@ -1,3 +1,12 @@
+#![allow(
+    unused,
+    clippy::borrowed_box,
+    clippy::explicit_auto_deref,
+    clippy::let_and_return,
+    clippy::needless_borrow,
+    clippy::ptr_arg,
+)]
+
 fn main() {
     let m1 = String::from("Hello");
     let m2 = String::from("world");
@@ -6,7 +15,7 @@ fn main() {
         println!("{}, {}!", g1, g2);
     }
     greet(m1, m2);
-    // let s = format!("{} {}", m1, m2); // error: m1 and m2 are moved
+    // let s = format!("{} {}", m1, m2); // m1 and m2 are moved, error[E0382]: borrow of moved value: `m1`
 
     // Alternative greet that returns ownership
     let m1 = String::from("Hello");
@@ -74,7 +83,7 @@ fn main() {
     let _num: &i32 = &v[2]; // Stack v -> Heap [1, 2, 3], num -> Heap 3
     v.push(4); // Stack v -> Heap [1, 2, 3, 4], num is invalidated and dropped!
 
-    // println!("The third element is {}", *_num); // error: immutable borrow later used here
+    // println!("The third element is {}", *_num); // immutable borrow later used here, error[E0502]: cannot borrow `v` as mutable
 
     // References Change Permissions on Paths
     // Variables have are 3 kinds of permissions on their data:
@@ -186,7 +195,7 @@ fn main() {
     let s_ref = &s;
 
     drop(s);
-    // println!("{}", s_ref); // error: s_ref is a reference to a dropped value
+    // println!("{}", s_ref); // s_ref is a reference to a dropped value, error[E0505]: cannot move out of `s`
 
     // The Flow Permission: F🟩
     // Flow is expected whenever an expression uses an input reference
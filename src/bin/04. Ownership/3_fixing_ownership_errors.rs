@@ -1,3 +1,5 @@
+#![allow(unused, clippy::let_and_return, clippy::ptr_arg, clippy::single_char_add_str)]
+
 // 1. Returning a Reference to the Stack
 
 // fn return_a_string() -> &String {
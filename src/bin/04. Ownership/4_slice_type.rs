@@ -1,3 +1,5 @@
+#![allow(unused, clippy::redundant_slicing)]
+
 fn main() {
     fn first_word(s: &String) -> usize {
         let bytes = s.as_bytes();
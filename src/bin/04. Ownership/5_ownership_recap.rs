@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 // Ownership vs Garbage Collection
 
 type Document = Vec<String>;
@@ -182,7 +184,7 @@ fn main() {
     // <- the data on the heap is moved to a new location (because the vector is full)
     //    and the old location is freed, which invalidates the n pointer
 
-    // println!("{n}"); error!
+    // println!("{n}"); // error!
 
     // Another type of undefined behavior is double-free, which occurs when a variable is freed twice
 
@@ -190,7 +192,7 @@ fn main() {
     let v_ref: &Vec<i32> = &v;
 
     // Dereferences of references to non-copyable data do not have the O🟥 permission to avoid double-frees
-    // let v2 = *v_ref; error!
+    // let v2 = *v_ref; // error!
     // <- v and v2 refer to the same data on the heap, while v_ref refers to v on the stack
 
     // drop(v2);
@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 fn main() {
     struct User {
         active: bool,
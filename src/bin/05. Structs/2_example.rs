@@ -1,8 +1,4 @@
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
+use learning_rust::structs::Rectangle;
 
 fn main() {
     let scale = 2;
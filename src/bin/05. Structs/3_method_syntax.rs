@@ -1,40 +1,7 @@
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
-
-impl Rectangle {
-    fn area(&self) -> u32 {
-        self.width * self.height
-    }
-
-    fn width(&self) -> bool {
-        self.width > 0
-    }
-
-    fn set_width(&mut self, width: u32) {
-        self.width = width;
-    }
+#![allow(unused, clippy::explicit_auto_deref)]
 
-    fn max(self, other: Self) -> Self {
-        Self {
-            width: self.width.max(other.width),
-            height: self.height.max(other.height),
-        }
-    }
-
-    fn can_hold(&self, other: &Self) -> bool {
-        self.width > other.width && self.height > other.height
-    }
-
-    fn square(size: u32) -> Self {
-        Self {
-            width: size,
-            height: size,
-        }
-    }
-}
+// Rectangle and its methods are defined in src/structs.rs, so other lessons and tests can use them
+use learning_rust::structs::Rectangle;
 
 fn main() {
     let scale = 2;
@@ -161,13 +128,11 @@ fn main() {
     // error!
     // println!("{}", rect.area()); // - rect: R🟧
 
-    impl Rectangle {
-        fn set_to_max(&mut self, other: Self) {
-            // - *self: R🟧, W🟦
+    fn set_to_max(rect: &mut Rectangle, other: Rectangle) {
+        // - *rect: R🟧, W🟦
 
-            // error: cannot move out of `*self` which is behind a mutable reference
-            // *self = self.max(other); // - *self: R🟧, W🟦 - self: R🟧, O🟥
-        }
+        // error: cannot move out of `*rect` which is behind a mutable reference
+        // *rect = rect.max(other); // - *rect: R🟧, W🟦 - rect: R🟧, O🟥
     }
 
     // Good Moves and Bad Moves
@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 // Defining an Enum
 
 enum IpAddrKind {
@@ -73,5 +75,5 @@ fn main() {
     let x: i8 = 5;
     let y: Option<i8> = Some(5);
 
-    // let sum = x + y; error: mismatched types
+    // let sum = x + y; // error[E0277]: cannot add `Option<i8>` to `i8`
 }
@@ -1,3 +1,5 @@
+#![allow(unused, clippy::manual_map)]
+
 use rand::Rng;
 
 enum Coin {
@@ -73,6 +75,7 @@ fn main() {
 
     // Matches Are Exhaustive
 
+    // error[E0004]
     // fn plus_one3(x: Option<i32>) -> i32 {
     //     match x { // error: non-exhaustive patterns: `None` not covered
     //         Some(i) => i + 1,
@@ -86,7 +89,7 @@ fn main() {
     fn move_player(num_spaces: u8) {}
     fn reroll() {}
 
-    let dice_roll = rand::thread_rng().gen_range(1..=6);
+    let dice_roll = learning_rust::rng().gen_range(1..=6);
 
     match dice_roll {
         3 => add_fancy_hat(),
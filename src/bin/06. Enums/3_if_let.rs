@@ -1,3 +1,5 @@
+#![allow(unused, clippy::single_match)]
+
 fn main() {
     let config_max = Some(3u8);
     match config_max {
@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 fn deliver_order() {}
 
 mod back_of_house {
@@ -1,3 +1,5 @@
+#![allow(unused)]
+
 use crate::garden::vegetables::Asparagus;
 pub mod garden;
 
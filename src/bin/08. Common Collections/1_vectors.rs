@@ -1,3 +1,5 @@
+#![allow(unused, clippy::ptr_arg, clippy::useless_vec, clippy::vec_init_then_push)]
+
 fn main() {
     // Creating a Vector
     let v: Vec<i32> = Vec::new();
@@ -73,7 +75,7 @@ fn main() {
     {
         let vector = vec![1, 2, 3, 4];
     } // <- vector goes out of scope and is freed here
-      // println!("{vector:?}"); error!
+      // println!("{vector:?}"); // error!
 
     let n: &i32;
     {
@@ -81,5 +83,5 @@ fn main() {
 
         n = &vector[3];
     } // <- vector goes out of scope and is freed here
-      // println!("{n}"); // error: borrowed value does not live long enough
+      // println!("{n}"); // error[E0597]: `vector` does not live long enough
 }
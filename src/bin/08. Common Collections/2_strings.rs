@@ -1,3 +1,5 @@
+#![allow(unused, clippy::iter_nth_zero)]
+
 use std::fmt::format;
 
 fn main() {
@@ -59,7 +61,7 @@ fn main() {
 
     // Indexing into Strings
     let s1 = String::from("hello");
-    // let h = s1[0]; // error: `std::string::String` cannot be indexed by `{integer}`
+    // let h = s1[0]; // error[E0277]: the type `str` cannot be indexed by `{integer}`
 
     let hello = String::from("hello");
     println!("{}", hello.len()); // 5
@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+#![allow(unused)]
+
+use std::collections::{BTreeMap, HashMap};
 
 fn main() {
     // Creating a New HashMap
@@ -11,7 +13,7 @@ fn main() {
     let team_name = String::from("Blue");
     let score = scores.get(&team_name).copied().unwrap_or(0);
 
-    for (key, value) in &scores {
+    for (key, value) in sorted(&scores) {
         println!("{key}: {value}");
     }
 
@@ -39,7 +41,7 @@ fn main() {
     scores.entry(String::from("Yellow")).or_insert(50);
     scores.entry(String::from("Blue")).or_insert(50);
 
-    println!("{scores:?}");
+    println!("{:?}", sorted(&scores));
 
     // Updating a value based on the old value
     let text = "hello world wonderful world";
@@ -51,7 +53,7 @@ fn main() {
         *count += 1;
     }
 
-    println!("{map:?}");
+    println!("{:?}", sorted(&map));
 
     // Exercise 1:
 
@@ -87,3 +89,9 @@ fn main() {
 
     println!("Median: {median}, Mode: {mode}");
 }
+
+// HashMaps iterate in an arbitrary order that changes between runs,
+// so they are printed through a BTreeMap, which iterates in key order
+fn sorted<K: Ord, V>(map: &HashMap<K, V>) -> BTreeMap<&K, &V> {
+    map.iter().collect()
+}
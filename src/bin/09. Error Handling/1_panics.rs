@@ -1,3 +1,5 @@
+#![allow(clippy::no_effect, clippy::useless_vec)]
+
 fn main() {
     // panic!("crash and burn");
     let v = vec![1, 2, 3];
@@ -1,3 +1,5 @@
+#![allow(unused, clippy::question_mark)]
+
 use std::error;
 use std::fs::{self, File};
 use std::io::{self, Error, ErrorKind, Read};
@@ -94,7 +96,10 @@ fn main() -> Result<(), Box<dyn error::Error>> {
 
     // Where the ? operator can be useful
 
-    // let greeting_file = File::open("hello.txt")?; // error!
+    // error[E0277]
+    // fn open_greeting() {
+    //     let greeting_file = File::open("hello.txt")?; // error!
+    // }
 
     fn last_char_of_first_line(text: &str) -> Option<char> {
         text.lines().next()?.chars().last()
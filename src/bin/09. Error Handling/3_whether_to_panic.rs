@@ -1,6 +1,10 @@
+#![allow(unused, clippy::manual_range_contains)]
+
 use std::net::IpAddr;
 use std::cmp::Ordering;
 
+use learning_rust::error_handling::Guess;
+
 fn main() {
     let home: IpAddr = "127.0.0.1"
         .parse()
@@ -30,31 +34,8 @@ fn main() {
         }
     }
 
-    pub struct Guess {
-        value: i32,
-    }
-
-    impl Guess {
-        pub fn new(value: i32) -> Guess {
-            Guess::validate_value(value);
-            Guess { value }
-        }
-
-        pub fn get(&self) -> i32 {
-            self.value
-        }
-
-        pub fn set(&mut self, value: i32) {
-            Guess::validate_value(value);
-            self.value = value;
-        }
-
-        fn validate_value(value: i32) {
-            if value < 1 || value > 100 {
-                panic!("Guess value must be between 1 and 100, got {}.", value);
-            }
-        }
-    }
+    // Creating Custom Types for Validation
+    // Guess checks the range once when it is created, see src/error_handling.rs
 
     let mut guess = Guess::new(12);
     guess.set(13);
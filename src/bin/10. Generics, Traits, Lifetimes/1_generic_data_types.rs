@@ -1,3 +1,7 @@
+#![allow(unused, non_camel_case_types)]
+
+use learning_rust::generics_traits_lifetimes::Point;
+
 fn largest_i32(list: &[i32]) -> &i32 {
     let mut largest = &list[0];
 
@@ -48,10 +52,7 @@ fn main() {
     println!("The largest char is {}", result);
 
     // In Struct Definitions
-    struct Point<T> {
-        x: T,
-        y: T,
-    }
+    // Point<T> is defined in src/generics_traits_lifetimes.rs
 
     let integer = Point { x: 5, y: 10 };
     let float = Point { x: 1.0, y: 4.0 };
@@ -78,22 +79,12 @@ fn main() {
     }
 
     // In Method Definitions
-    impl<T> Point<T> {
-        fn x(&self) -> &T {
-            &self.x
-        }
-    }
+    // impl<T> Point<T> has x(), impl Point<f32> has distance_from_origin()
 
     let p = Point { x: 5, y: 10 };
 
     println!("p.x = {}", p.x());
 
-    impl Point<f32> {
-        fn distance_from_origin(&self) -> f32 {
-            (self.x.powi(2) + self.y.powi(2)).sqrt()
-        }
-    }
-
     let p1 = Point { x: 5.0, y: 10.0 };
     let p2 = Point { x: 5, y: 10 };
 
@@ -1,28 +1,9 @@
-// Defining a Trait
-pub trait Summary {
-    fn summarize(&self) -> String;
-}
+#![allow(unused, clippy::extra_unused_type_parameters, clippy::if_same_then_else)]
 
+// Defining a Trait
 // Implementing a Trait on a Type
-pub struct NewsArticle {
-    pub headline: String,
-    pub location: String,
-    pub author: String,
-    pub content: String,
-}
-
-pub struct Tweet {
-    pub username: String,
-    pub content: String,
-    pub reply: bool,
-    pub retweet: bool,
-}
-
-impl Summary for Tweet {
-    fn summarize(&self) -> String {
-        format!("{}: {}", self.username, self.content)
-    }
-}
+// Summary, NewsArticle and Tweet are defined in src/generics_traits_lifetimes.rs
+use learning_rust::generics_traits_lifetimes::{NewsArticle, Summary, Tweet};
 
 fn main() {
     let tweet = Tweet {
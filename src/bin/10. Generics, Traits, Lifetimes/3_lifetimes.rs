@@ -1,3 +1,7 @@
+#![allow(unused, clippy::redundant_slicing)]
+
+use learning_rust::generics_traits_lifetimes::ImportantExcerpt;
+
 fn main() {
     // Preventing Dangling References with Lifetimes
     let r;
@@ -13,8 +17,9 @@ fn main() {
 
     // Generic Lifetimes in Functions
 
-    // error! missing lifetime specifier: this function's return type contains a
+    // Missing lifetime specifier: this function's return type contains a
     // borrowed value, but the signature does not say whether it is borrowed from `x` or `y`
+    // error[E0106]
     // fn longest(x: &str, y: &str) -> &str {
     //     if x.len() > y.len() {
     //         x
@@ -57,7 +62,7 @@ fn main() {
         let string2 = String::from("xyz");
         result = longest(string1.as_str(), string2.as_str());
     }
-    // println!("The longest string is {}", result); error! `string2` does not live long enough
+    // println!("The longest string is {}", result); // error! `string2` does not live long enough
 
     // Thinking in Terms of Lifetimes
     fn longest2<'a>(x: &'a str, y: &str) -> &'a str {
@@ -71,9 +76,7 @@ fn main() {
     // }
 
     // Lifetime Annotations in Struct Definitions
-    struct ImportantExcerpt<'a> {
-        part: &'a str,
-    }
+    // ImportantExcerpt<'a> is defined in src/generics_traits_lifetimes.rs
 
     let novel = String::from("Call me Ishmael. Some years ago...");
     let first_sentence = novel.split('.').next().expect("Could not find a '.'");
@@ -95,16 +98,8 @@ fn main() {
     }
 
     // Lifetime Annotations in Method Definitions
-    impl<'a> ImportantExcerpt<'a> {
-        fn level(&self) -> i32 {
-            3
-        }
-
-        fn announce_and_return_part(&self, announcement: &str) -> &str {
-            println!("Attention please: {}", announcement);
-            self.part
-        }
-    }
+    let part = i.announce_and_return_part("Here is the first sentence");
+    println!("{part} (level {})", i.level());
 
     // The Static Lifetime
 
@@ -1,3 +1,7 @@
+//! target = "test"
+
+#![allow(unused, clippy::manual_range_contains)]
+
 use std::fmt::format;
 
 fn main() {}
@@ -1,3 +1,5 @@
+//! target = "test"
+
 fn main() {}
 
 pub fn add_two(a: i32) -> i32 {
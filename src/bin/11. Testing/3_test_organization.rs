@@ -1,3 +1,5 @@
+//! target = "test"
+
 fn main() {}
 
 // Unit Tests
@@ -21,9 +23,10 @@ mod tests {
 }
 
 // Integration Tests
-// in the tests/ directory
+// in the tests/ directory, they only see the public API of the library crate
+// this repo's library is src/lib.rs, see tests/structs.rs
 
-// use adder;
+// use learning_rust::structs::Rectangle;
 
 #[test]
 fn it_adds_two() {
@@ -0,0 +1,65 @@
+//! Compares `-i` searches with the `to_lowercase` one minigrep started with,
+//! which allocates a lowercased copy of every line:
+//!
+//! ```sh
+//! cargo bench -p minigrep
+//! ```
+
+use minigrep::{Fixed, Folding, Matcher};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LINES: [&str; 7] = [
+    "I'm nobody! Who are you?",
+    "Are you nobody, too?",
+    "Then there's a pair of us - don't tell!",
+    "Die Straße ist lang.",
+    "Die STRASSE ist breit.",
+    "Ο ΣΟΦΟΣ λέει πάντα την αλήθεια, ο σοφός.",
+    "How dreary to be somebody!",
+];
+
+fn main() {
+    let contents = LINES.repeat(50_000).join("\n");
+
+    for query in ["to", "strasse", "σοφος"] {
+        println!("{query:?} in {} KiB", contents.len() / 1024);
+
+        bench("to_lowercase", || {
+            let query = query.to_lowercase();
+            contents
+                .lines()
+                .filter(|line| line.to_lowercase().contains(&query))
+                .count()
+        });
+
+        for (name, folding) in [
+            ("simple folding", Folding::Simple),
+            ("full folding", Folding::Full),
+        ] {
+            let matcher = Fixed::new(query, false).folding(folding);
+            bench(name, || {
+                contents
+                    .lines()
+                    .filter(|line| matcher.is_match(line))
+                    .count()
+            });
+        }
+    }
+}
+
+/// Runs `search` for about a second, prints the time of the fastest run and
+/// the lines it found.
+fn bench(name: &str, mut search: impl FnMut() -> usize) {
+    let start = Instant::now();
+    let mut fastest = Duration::MAX;
+    let mut found = 0;
+
+    while start.elapsed() < Duration::from_secs(1) {
+        let run = Instant::now();
+        found = black_box(search());
+        fastest = fastest.min(run.elapsed());
+    }
+
+    println!("  {name:<16}{:>10.2?}  {found} lines", fastest);
+}
@@ -0,0 +1,406 @@
+use crate::{ColorChoice, Flags, Folding};
+use std::error::Error;
+use std::fmt;
+
+/// What is wrong with the command line.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ArgError {
+    /// No query and no flags either.
+    MissingQuery,
+    /// A flag that is not in the help, with the closest one there is.
+    UnknownFlag {
+        flag: String,
+        suggestion: Option<String>,
+    },
+    /// A flag like `-A` at the end of the arguments.
+    MissingValue { flag: String, name: &'static str },
+    InvalidValue {
+        flag: String,
+        value: String,
+        expected: &'static str,
+    },
+    /// A value for a switch, like `--count=3`.
+    UnexpectedValue { flag: String, value: String },
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::MissingQuery => write!(f, "Expected a query"),
+            ArgError::UnknownFlag { flag, suggestion } => {
+                write!(f, "Unknown flag {flag}")?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean {suggestion}?"),
+                    None => Ok(()),
+                }
+            }
+            ArgError::MissingValue { flag, name } => write!(f, "Expected {name} after {flag}"),
+            ArgError::InvalidValue {
+                flag,
+                value,
+                expected,
+            } => write!(f, "Invalid value {value:?} for {flag}, expected {expected}"),
+            ArgError::UnexpectedValue { flag, value } => {
+                write!(f, "{flag} does not take a value, got {value:?}")
+            }
+        }
+    }
+}
+
+impl Error for ArgError {}
+
+/// What a flag does with the argument after it.
+enum Takes {
+    Nothing(Flags),
+    /// A value, shown as `name` in the help, like `N`. `parse` fails with what
+    /// it expected.
+    Value {
+        name: &'static str,
+        parse: fn(&str) -> Result<Flags, &'static str>,
+    },
+}
+
+/// A flag as it is written on the command line and in the help.
+struct Spec {
+    short: Option<char>,
+    long: &'static str,
+    takes: Takes,
+    help: &'static str,
+}
+
+const fn switch(short: Option<char>, long: &'static str, flag: Flags, help: &'static str) -> Spec {
+    Spec {
+        short,
+        long,
+        takes: Takes::Nothing(flag),
+        help,
+    }
+}
+
+const fn value(
+    short: Option<char>,
+    long: &'static str,
+    name: &'static str,
+    parse: fn(&str) -> Result<Flags, &'static str>,
+    help: &'static str,
+) -> Spec {
+    Spec {
+        short,
+        long,
+        takes: Takes::Value { name, parse },
+        help,
+    }
+}
+
+fn case_folding(value: &str) -> Result<Flags, &'static str> {
+    Folding::parse(value)
+        .map(Flags::CaseFolding)
+        .ok_or("full or simple")
+}
+
+fn color(value: &str) -> Result<Flags, &'static str> {
+    ColorChoice::parse(value)
+        .map(Flags::Color)
+        .ok_or("auto, always or never")
+}
+
+fn lines(value: &str) -> Result<usize, &'static str> {
+    value.parse().map_err(|_| "a number of lines")
+}
+
+/// Every flag, in the order of the help.
+#[rustfmt::skip]
+const FLAGS: &[Spec] = &[
+    switch(Some('h'), "help", Flags::Help, "Prints help information"),
+    switch(Some('V'), "version", Flags::Version, "Prints version information"),
+    switch(Some('i'), "ignore-case", Flags::IgnoreCase, "Case insensitive search"),
+    switch(None, "no-ignore-case", Flags::NoIgnoreCase, "Case sensitive search, even when a setting turns -i on"),
+    value(None, "case-folding", "MODE", case_folding, "How -i folds the query: full (default, ß matches ss) or simple"),
+    switch(Some('E'), "extended-regexp", Flags::ExtendedRegexp, "The query is a regular expression"),
+    switch(Some('w'), "word-regexp", Flags::WordRegexp, "Only match whole words"),
+    switch(Some('x'), "line-regexp", Flags::LineRegexp, "Only match whole lines"),
+    switch(Some('v'), "invert-match", Flags::InvertMatch, "Select the lines that do not match"),
+    switch(Some('c'), "count", Flags::Count, "Print the number of selected lines in each file"),
+    switch(Some('l'), "files-with-matches", Flags::FilesWithMatches, "Print the files with a selected line"),
+    switch(Some('L'), "files-without-match", Flags::FilesWithoutMatch, "Print the files without a selected line"),
+    switch(Some('q'), "quiet", Flags::Quiet, "Print nothing, stop at the first selected line"),
+    switch(None, "json", Flags::Json, "Print JSON Lines, see the docs of minigrep::json"),
+    switch(None, "first-file", Flags::FirstFile, "Search the first file a name matches"),
+    switch(None, "all-files", Flags::AllFiles, "Search every file a name matches (default)"),
+    switch(None, "unique-file", Flags::UniqueFile, "Fail when a name matches more than one file"),
+    switch(Some('n'), "line-number", Flags::LineNumber, "Print the line number of each match"),
+    switch(None, "no-line-number", Flags::NoLineNumber, "Do not print line numbers, even when a setting turns -n on"),
+    switch(Some('b'), "byte-offset", Flags::ByteOffset, "Print the byte offset of each line in its file"),
+    switch(None, "column", Flags::Column, "Print the line and column of each match"),
+    switch(None, "no-column", Flags::NoColumn, "Do not print columns, even when a setting turns --column on"),
+    value(Some('A'), "after-context", "N", |v| lines(v).map(Flags::AfterContext), "Print N lines after each match"),
+    value(Some('B'), "before-context", "N", |v| lines(v).map(Flags::BeforeContext), "Print N lines before each match"),
+    value(Some('C'), "context", "N", |v| lines(v).map(Flags::Context), "Print N lines before and after each match"),
+    value(None, "color", "WHEN", color, "Color the output: auto (default), always or never"),
+    switch(None, "show-config", Flags::ShowConfig, "Print the settings and where each one comes from"),
+    switch(Some('d'), "debug", Flags::Debug, "Debug mode"),
+];
+
+/// What the help says between the usage and the flags.
+const ABOUT: &str = "\
+A path is a file, a directory to search every file in, or a file name
+to look for in every directory below the current one. Without paths,
+or with `-`, minigrep searches stdin.
+
+Short flags can be combined, like -in or -nC2. Everything after --
+is the query or a path, for queries that start with -.
+
+Settings are read from ~/.config/minigrep/config.toml, then from
+MINIGREP_* environment variables like MINIGREP_LINE_NUMBER=1, then
+from the flags. See them with --show-config.";
+
+impl Spec {
+    /// How the flag is shown in the help, like `-A, --after-context N`.
+    fn name(&self) -> String {
+        let short = self
+            .short
+            .map_or(String::from("    "), |short| format!("-{short}, "));
+        match self.takes {
+            Takes::Nothing(_) => format!("{short}--{}", self.long),
+            Takes::Value { name, .. } => format!("{short}--{} {name}", self.long),
+        }
+    }
+
+    /// The flag, `flag` as it was written. A value that is not part of the
+    /// argument, like in `-A 3`, is the next one of `args`.
+    fn take<'a>(
+        &self,
+        flag: String,
+        value: Option<&'a str>,
+        args: &mut impl Iterator<Item = &'a str>,
+    ) -> Result<Flags, ArgError> {
+        match (&self.takes, value) {
+            (Takes::Nothing(flag), None) => Ok(*flag),
+            (Takes::Nothing(_), Some(value)) => Err(ArgError::UnexpectedValue {
+                flag,
+                value: value.to_string(),
+            }),
+            (Takes::Value { name, parse }, value) => {
+                let Some(value) = value.or_else(|| args.next()) else {
+                    return Err(ArgError::MissingValue { flag, name });
+                };
+                parse(value).map_err(|expected| ArgError::InvalidValue {
+                    flag,
+                    value: value.to_string(),
+                    expected,
+                })
+            }
+        }
+    }
+}
+
+/// The flags and the other arguments, in order, without the program name.
+pub(crate) fn parse<'a>(
+    args: impl IntoIterator<Item = &'a str>,
+) -> Result<(Vec<Flags>, Vec<&'a str>), ArgError> {
+    let mut flags = Vec::new();
+    let mut positionals = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positionals.extend(args.by_ref());
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let Some(spec) = FLAGS.iter().find(|spec| spec.long == name) else {
+                return Err(ArgError::UnknownFlag {
+                    flag: format!("--{name}"),
+                    suggestion: closest(name),
+                });
+            };
+            flags.push(spec.take(format!("--{name}"), value, &mut args)?);
+        } else if let Some(shorts) = arg.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+            // Switches until one that takes a value, which takes the rest
+            for (i, short) in shorts.char_indices() {
+                let Some(spec) = FLAGS.iter().find(|spec| spec.short == Some(short)) else {
+                    return Err(ArgError::UnknownFlag {
+                        flag: format!("-{short}"),
+                        // Like -color for --color
+                        suggestion: closest(shorts).filter(|_| shorts.len() > 1),
+                    });
+                };
+                let rest = &shorts[i + short.len_utf8()..];
+                if let Takes::Value { .. } = spec.takes {
+                    let value = Some(rest).filter(|rest| !rest.is_empty());
+                    flags.push(spec.take(format!("-{short}"), value, &mut args)?);
+                    break;
+                }
+                flags.push(spec.take(format!("-{short}"), None, &mut args)?);
+            }
+        } else {
+            positionals.push(arg);
+        }
+    }
+
+    Ok((flags, positionals))
+}
+
+/// The long flag closest to `name`, if it is close enough to be a typo or
+/// `name` is the start of only one of them.
+fn closest(name: &str) -> Option<String> {
+    let mut starting = FLAGS.iter().filter(|spec| spec.long.starts_with(name));
+    if let (Some(spec), None) = (starting.next(), starting.next()) {
+        return Some(format!("--{}", spec.long));
+    }
+
+    FLAGS
+        .iter()
+        .map(|spec| (distance(name, spec.long), spec.long))
+        .filter(|(distance, long)| *distance <= 2.max(long.len() / 4))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, long)| format!("--{long}"))
+}
+
+/// The Levenshtein distance, the number of chars to add, remove or replace
+/// to turn `a` into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != *b);
+            current.push(replace.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The usage line, with every short switch.
+pub fn usage() -> String {
+    let switches: String = FLAGS
+        .iter()
+        .filter(|spec| matches!(spec.takes, Takes::Nothing(_)))
+        .filter_map(|spec| spec.short)
+        .collect();
+    format!("Usage: minigrep [-{switches}] [OPTIONS] [--] <query> [<path>...]")
+}
+
+/// The usage, what the arguments mean and every flag.
+pub fn help() -> String {
+    let width = FLAGS
+        .iter()
+        .map(|spec| spec.name().len())
+        .max()
+        .unwrap_or(0);
+    let flags: Vec<String> = FLAGS
+        .iter()
+        .map(|spec| format!("  {:<width$}  {}", spec.name(), spec.help))
+        .collect();
+
+    format!("{}\n\n{ABOUT}\n\nOptions:\n{}", usage(), flags.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combined_short_flags() {
+        let (flags, positionals) = parse(["-in", "-vA3", "rust", "-C", "1", "-"]).unwrap();
+
+        assert_eq!(
+            vec![
+                Flags::IgnoreCase,
+                Flags::LineNumber,
+                Flags::InvertMatch,
+                Flags::AfterContext(3),
+                Flags::Context(1),
+            ],
+            flags
+        );
+        assert_eq!(vec!["rust", "-"], positionals);
+    }
+
+    #[test]
+    fn end_of_flags() {
+        let (flags, positionals) = parse(["-i", "--", "-n", "--color=always"]).unwrap();
+
+        assert_eq!(vec![Flags::IgnoreCase], flags);
+        assert_eq!(vec!["-n", "--color=always"], positionals);
+    }
+
+    #[test]
+    fn long_values() {
+        let (flags, _) = parse(["--color=never", "--case-folding", "simple"]).unwrap();
+
+        assert_eq!(
+            vec![
+                Flags::Color(ColorChoice::Never),
+                Flags::CaseFolding(Folding::Simple)
+            ],
+            flags
+        );
+        assert!(parse(["--context="]).is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Err(ArgError::UnknownFlag {
+                flag: String::from("--colour"),
+                suggestion: Some(String::from("--color")),
+            }),
+            parse(["--colour=always"])
+        );
+        assert_eq!(
+            "Unknown flag -o, did you mean --color?",
+            parse(["-color"]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Unknown flag --frobnicate",
+            parse(["--frobnicate"]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Expected N after -A",
+            parse(["rust", "-nA"]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Invalid value \"x\" for --context, expected a number of lines",
+            parse(["--context=x"]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "--count does not take a value, got \"3\"",
+            parse(["--count=3"]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn typos() {
+        assert_eq!(Some(String::from("--ignore-case")), closest("ignorecase"));
+        assert_eq!(
+            Some(String::from("--files-with-matches")),
+            closest("file-with-match")
+        );
+        assert_eq!(None, closest("xyz"));
+
+        assert_eq!(Some(String::from("--ignore-case")), closest("ignor"));
+        assert_eq!(
+            Some(String::from("--files-without-match")),
+            closest("files-witho")
+        );
+        assert_eq!(None, closest("files-with"));
+        assert_eq!(3, distance("kitten", "sitting"));
+    }
+
+    #[test]
+    fn help_lists_every_flag() {
+        let help = help();
+
+        assert!(help.starts_with("Usage: minigrep [-hViEwxvclLqnbd] [OPTIONS]"));
+        for spec in FLAGS {
+            assert!(help.contains(&format!("--{} ", spec.long)));
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+/// When to color the output, `--color=WHEN`.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum ColorChoice {
+    /// When printing to a terminal and `NO_COLOR` is not set, the default.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<ColorChoice> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Whether to color the output. `no_color` is whether `NO_COLOR` is set to
+/// anything but an empty string, see <https://no-color.org>.
+pub fn use_color(choice: ColorChoice, no_color: bool, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Auto => is_terminal && !no_color,
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
+}
+
+/// The SGR parameters for each part of the output, like `01;31` for bold red.
+/// An empty one leaves that part uncolored.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Colors {
+    pub matched: String,
+    pub file: String,
+    pub line_number: String,
+    pub column: String,
+    pub byte_offset: String,
+    pub separator: String,
+}
+
+/// The same colors as grep.
+impl Default for Colors {
+    fn default() -> Colors {
+        Colors {
+            matched: String::from("01;31"),
+            file: String::from("35"),
+            line_number: String::from("32"),
+            column: String::from("32"),
+            byte_offset: String::from("32"),
+            separator: String::from("36"),
+        }
+    }
+}
+
+impl Colors {
+    /// No colors at all.
+    pub fn none() -> Colors {
+        Colors {
+            matched: String::new(),
+            file: String::new(),
+            line_number: String::new(),
+            column: String::new(),
+            byte_offset: String::new(),
+            separator: String::new(),
+        }
+    }
+
+    /// The default colors changed by a `MINIGREP_COLORS` value, which works
+    /// like `GREP_COLORS`: `mt=01;32:fn=34:se=`. The keys are `mt` for matches,
+    /// `fn` for file names, `ln` for line numbers, `cn` for columns, `bn` for
+    /// byte offsets and `se` for separators. Unknown keys are ignored.
+    pub fn parse(spec: &str) -> Colors {
+        let mut colors = Colors::default();
+
+        for (key, value) in spec.split(':').filter_map(|pair| pair.split_once('=')) {
+            let color = match key {
+                "mt" => &mut colors.matched,
+                "fn" => &mut colors.file,
+                "ln" => &mut colors.line_number,
+                "cn" => &mut colors.column,
+                "bn" => &mut colors.byte_offset,
+                "se" => &mut colors.separator,
+                _ => continue,
+            };
+            *color = value.to_string();
+        }
+
+        colors
+    }
+
+    /// `line` with the `spans` in the match color.
+    pub fn highlight(&self, line: &str, spans: impl Iterator<Item = Range<usize>>) -> String {
+        let mut highlighted = String::new();
+        let mut end = 0;
+
+        for span in spans.filter(|span| !span.is_empty()) {
+            highlighted.push_str(&line[end..span.start]);
+            highlighted.push_str(&paint(&self.matched, &line[span.clone()]));
+            end = span.end;
+        }
+
+        highlighted + &line[end..]
+    }
+}
+
+/// `text` in the color of `sgr`.
+pub fn paint(sgr: &str, text: &str) -> String {
+    if sgr.is_empty() || text.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{sgr}m{text}\x1b[0m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choice() {
+        assert!(use_color(ColorChoice::Auto, false, true));
+        assert!(!use_color(ColorChoice::Auto, true, true));
+        assert!(!use_color(ColorChoice::Auto, false, false));
+        assert!(use_color(ColorChoice::Always, true, false));
+        assert!(!use_color(ColorChoice::Never, false, true));
+    }
+
+    #[test]
+    fn parse() {
+        let colors = Colors::parse("mt=01;32:fn=:xx=1:ln");
+
+        assert_eq!("01;32", colors.matched);
+        assert_eq!("", colors.file);
+        assert_eq!(Colors::default().line_number, colors.line_number);
+    }
+
+    #[test]
+    fn highlight() {
+        let colors = Colors::default();
+
+        assert_eq!(
+            "a \x1b[01;31mrust\x1b[0m b \x1b[01;31mrust\x1b[0m",
+            colors.highlight("a rust b rust", [0..0, 2..6, 9..13].into_iter())
+        );
+        assert_eq!(
+            "a rust",
+            Colors::none().highlight("a rust", std::iter::once(2..6))
+        );
+    }
+}
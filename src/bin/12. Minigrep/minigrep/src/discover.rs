@@ -0,0 +1,149 @@
+use crate::MinigrepError;
+use glob::{glob, Pattern};
+use std::path::{Path, PathBuf};
+
+/// What to search when a name matches several files under the current
+/// directory.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Ambiguity {
+    /// The first one in alphabetical order.
+    First,
+    /// All of them.
+    #[default]
+    All,
+    /// None, it is an error.
+    Error,
+}
+
+/// The files to search for a path argument, relative to `root`.
+///
+/// A file is searched as is and a directory with every file below it. Anything
+/// else is a name to look for in every directory below `root`, like
+/// `poem.txt` or `src/lib.rs`.
+pub fn discover_files(
+    root: &Path,
+    path: &str,
+    ambiguity: Ambiguity,
+) -> Result<Vec<PathBuf>, MinigrepError> {
+    if root.join(path).is_file() {
+        return Ok(vec![PathBuf::from(path)]);
+    }
+
+    // Joining keeps absolute paths as they are
+    let dir = root.join(path);
+    if dir.is_dir() {
+        let dir = Pattern::escape(&dir.display().to_string());
+        return find(root, &format!("{dir}/**/*"));
+    }
+
+    let below = Pattern::escape(&root.display().to_string());
+    let found = find(root, &format!("{below}/**/{}", Pattern::escape(path)))?;
+
+    match (found.len(), ambiguity) {
+        (0, _) => Err(MinigrepError::FileNotFound(PathBuf::from(path))),
+        (_, Ambiguity::First) => Ok(found.into_iter().take(1).collect()),
+        (1, _) | (_, Ambiguity::All) => Ok(found),
+        (_, Ambiguity::Error) => Err(MinigrepError::AmbiguousName {
+            name: path.to_string(),
+            files: found,
+        }),
+    }
+}
+
+/// The files matching `pattern`, with paths relative to `root` when they are
+/// below it.
+fn find(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, MinigrepError> {
+    let mut files = Vec::new();
+
+    for entry in glob(pattern)? {
+        let path = entry?;
+        if path.is_file() {
+            files.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    fn root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("minigrep-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        for file in ["poem.txt", "a/poem.txt", "b/poem.txt", "b/notes.txt"] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        root
+    }
+
+    #[test]
+    fn paths_and_directories() {
+        let root = root("paths");
+
+        assert_eq!(
+            vec![PathBuf::from("a/poem.txt")],
+            discover_files(&root, "a/poem.txt", Ambiguity::Error).unwrap()
+        );
+        assert_eq!(
+            vec![PathBuf::from("b/notes.txt"), PathBuf::from("b/poem.txt")],
+            discover_files(&root, "b", Ambiguity::Error).unwrap()
+        );
+        assert!(discover_files(&root, "missing.txt", Ambiguity::All).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn absolute_directories() {
+        let root = root("absolute");
+        let dir = root.join("b");
+
+        assert_eq!(
+            vec![dir.join("notes.txt"), dir.join("poem.txt")],
+            discover_files(
+                &root.join("a"),
+                &dir.display().to_string(),
+                Ambiguity::Error
+            )
+            .unwrap()
+        );
+        // Below the root they are relative, like the other paths
+        assert_eq!(
+            vec![PathBuf::from("b/notes.txt"), PathBuf::from("b/poem.txt")],
+            discover_files(&root, &dir.display().to_string(), Ambiguity::Error).unwrap()
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn ambiguous_names() {
+        let root = root("names");
+        fs::remove_file(root.join("poem.txt")).unwrap();
+
+        assert_eq!(
+            vec![PathBuf::from("a/poem.txt")],
+            discover_files(&root, "poem.txt", Ambiguity::First).unwrap()
+        );
+        assert_eq!(
+            vec![PathBuf::from("a/poem.txt"), PathBuf::from("b/poem.txt")],
+            discover_files(&root, "poem.txt", Ambiguity::All).unwrap()
+        );
+        assert_eq!(
+            "poem.txt matches more than one file:\n  a/poem.txt\n  b/poem.txt",
+            discover_files(&root, "poem.txt", Ambiguity::Error)
+                .unwrap_err()
+                .to_string()
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
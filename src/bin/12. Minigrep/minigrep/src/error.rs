@@ -0,0 +1,182 @@
+use crate::ArgError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Why a search failed. The message says where, [`Error::source`] says what
+/// went wrong there when it comes from another library.
+#[derive(Debug)]
+pub enum MinigrepError {
+    Args(ArgError),
+    /// A wrong setting in the config file or a `MINIGREP_*` variable, `origin`
+    /// is the file or the variable.
+    Settings {
+        origin: String,
+        message: String,
+    },
+    /// A config file that is not TOML.
+    ConfigFile {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A query that is not a regular expression, with `-E`.
+    Regex(regex::Error),
+    /// A path that is not a file, a directory or the name of a file below the
+    /// current directory.
+    FileNotFound(PathBuf),
+    /// A name that matches more than one file, with `--unique-file`.
+    AmbiguousName {
+        name: String,
+        files: Vec<PathBuf>,
+    },
+    PermissionDenied {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// A path that is not a glob pattern even after escaping it.
+    Glob(glob::PatternError),
+    /// A file that is not UTF-8. Only files given by path are errors, the ones
+    /// found in directories are skipped.
+    InvalidEncoding {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// Any other error reading `path`, or writing the output without one.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+}
+
+impl MinigrepError {
+    /// The error for reading `path`, by what went wrong.
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> MinigrepError {
+        let path = path.into();
+
+        match source.kind() {
+            io::ErrorKind::NotFound => MinigrepError::FileNotFound(path),
+            io::ErrorKind::PermissionDenied => MinigrepError::PermissionDenied { path, source },
+            io::ErrorKind::InvalidData => MinigrepError::InvalidEncoding { path, source },
+            _ => MinigrepError::Io {
+                path: Some(path),
+                source,
+            },
+        }
+    }
+}
+
+impl fmt::Display for MinigrepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MinigrepError::Args(_) => write!(f, "Invalid arguments"),
+            MinigrepError::Settings { origin, message } => write!(f, "{origin}: {message}"),
+            MinigrepError::ConfigFile { path, .. } => {
+                write!(f, "{}: invalid config file", path.display())
+            }
+            MinigrepError::Regex(_) => write!(f, "Invalid regular expression"),
+            MinigrepError::FileNotFound(path) => write!(f, "File not found: {}", path.display()),
+            MinigrepError::AmbiguousName { name, files } => {
+                write!(f, "{name} matches more than one file:")?;
+                for file in files {
+                    write!(f, "\n  {}", file.display())?;
+                }
+                Ok(())
+            }
+            MinigrepError::PermissionDenied { path, .. } => {
+                write!(f, "{}: permission denied", path.display())
+            }
+            MinigrepError::Glob(_) => write!(f, "Invalid glob pattern"),
+            MinigrepError::InvalidEncoding { path, .. } => {
+                write!(f, "{}: not UTF-8", path.display())
+            }
+            MinigrepError::Io {
+                path: Some(path), ..
+            } => write!(f, "{}: could not read", path.display()),
+            MinigrepError::Io { path: None, .. } => write!(f, "Could not write the output"),
+        }
+    }
+}
+
+impl Error for MinigrepError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MinigrepError::Args(source) => Some(source),
+            MinigrepError::ConfigFile { source, .. } => Some(source),
+            MinigrepError::Regex(source) => Some(source),
+            MinigrepError::Glob(source) => Some(source),
+            MinigrepError::PermissionDenied { source, .. }
+            | MinigrepError::InvalidEncoding { source, .. }
+            | MinigrepError::Io { source, .. } => Some(source),
+            MinigrepError::Settings { .. }
+            | MinigrepError::FileNotFound(_)
+            | MinigrepError::AmbiguousName { .. } => None,
+        }
+    }
+}
+
+impl From<ArgError> for MinigrepError {
+    fn from(e: ArgError) -> MinigrepError {
+        MinigrepError::Args(e)
+    }
+}
+
+impl From<regex::Error> for MinigrepError {
+    fn from(e: regex::Error) -> MinigrepError {
+        MinigrepError::Regex(e)
+    }
+}
+
+impl From<glob::PatternError> for MinigrepError {
+    fn from(e: glob::PatternError) -> MinigrepError {
+        MinigrepError::Glob(e)
+    }
+}
+
+/// An error reading a directory while globbing.
+impl From<glob::GlobError> for MinigrepError {
+    fn from(e: glob::GlobError) -> MinigrepError {
+        let path = e.path().to_path_buf();
+        MinigrepError::io(path, e.into())
+    }
+}
+
+/// An error writing the output.
+impl From<io::Error> for MinigrepError {
+    fn from(source: io::Error) -> MinigrepError {
+        MinigrepError::Io { path: None, source }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_errors() {
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        let e = MinigrepError::io("poem.txt", denied);
+
+        assert!(matches!(e, MinigrepError::PermissionDenied { .. }));
+        assert_eq!("poem.txt: permission denied", e.to_string());
+        assert!(e.source().is_some());
+
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        assert_eq!(
+            "File not found: poem.txt",
+            MinigrepError::io("poem.txt", missing).to_string()
+        );
+    }
+
+    #[test]
+    fn source_chains() {
+        let e = MinigrepError::from(crate::Regex::new("(", false).err().unwrap());
+        let source = e.source().unwrap();
+
+        assert_eq!("Invalid regular expression", e.to_string());
+        assert!(source.to_string().contains("unclosed group"));
+
+        let e = MinigrepError::from(ArgError::MissingQuery);
+        assert_eq!("Expected a query", e.source().unwrap().to_string());
+    }
+}
@@ -0,0 +1,196 @@
+//! The `--json` output: [JSON Lines](https://jsonlines.org), one event per
+//! line. Every event is an object with a `type` and the `version` of this
+//! schema, [`VERSION`]. New fields can be added in the same version, so ignore
+//! the ones you do not know.
+//!
+//! For each file searched, in order:
+//!
+//! - `begin`: `path`, the file searched. For a path argument that is a file,
+//!   it is the argument as given. For a directory or a name, it is each file
+//!   found, relative to the current directory when it is below it and
+//!   absolute otherwise, like `src/lib.rs` for the name `lib.rs`. Stdin is
+//!   `-`.
+//! - `match`, a selected line: `path`, `line_number` (1-based), `byte_offset`
+//!   (of the line in the file), `line` (without its line ending) and
+//!   `submatches`, each with the `start` and `end` byte offsets in `line` and
+//!   the `text` matched. Lines selected by `-v` have no submatches.
+//! - `context`, a line printed because of `-A`, `-B` or `-C`: `path`,
+//!   `line_number`, `byte_offset` and `line`.
+//! - `end`: `path` and `matches`, the number of selected lines. A file that
+//!   could not be read all the way also ends.
+//!
+//! And once all files are searched, `summary`: `files`, the number of files
+//! searched, and `matches`, the selected lines in all of them. Paths that
+//! cannot be searched have no events, they are reported on stderr and
+//! minigrep exits with 2 after the summary.
+//!
+//! ```text
+//! {"type":"begin","version":1,"path":"poem.txt"}
+//! {"type":"match","version":1,"path":"poem.txt","line_number":2,"byte_offset":5,"line":"fő: rust","submatches":[{"start":5,"end":9,"text":"rust"}]}
+//! {"type":"end","version":1,"path":"poem.txt","matches":1}
+//! {"type":"summary","version":1,"files":1,"matches":1}
+//! ```
+
+use crate::Match;
+use std::fmt;
+use std::ops::Range;
+
+/// The version of the schema, changed when a field changes or goes away.
+pub const VERSION: u32 = 1;
+
+/// One line of the output.
+#[derive(Debug, PartialEq)]
+pub enum Event<'a> {
+    Begin {
+        path: &'a str,
+    },
+    Match {
+        path: &'a str,
+        found: &'a Match<'a>,
+        submatches: &'a [Range<usize>],
+    },
+    Context {
+        path: &'a str,
+        line_number: usize,
+        byte_offset: usize,
+        line: &'a str,
+    },
+    End {
+        path: &'a str,
+        matches: usize,
+    },
+    Summary {
+        files: usize,
+        matches: usize,
+    },
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            Event::Begin { .. } => "begin",
+            Event::Match { .. } => "match",
+            Event::Context { .. } => "context",
+            Event::End { .. } => "end",
+            Event::Summary { .. } => "summary",
+        };
+        write!(f, "{{\"type\":\"{kind}\",\"version\":{VERSION}")?;
+
+        match self {
+            Event::Begin { path } => write!(f, ",\"path\":{}", string(path))?,
+            Event::Match {
+                path,
+                found,
+                submatches,
+            } => {
+                write!(
+                    f,
+                    ",\"path\":{},\"line_number\":{},\"byte_offset\":{},\"line\":{}",
+                    string(path),
+                    found.line_number,
+                    found.byte_offset,
+                    string(found.line)
+                )?;
+                let submatches: Vec<String> = submatches
+                    .iter()
+                    .map(|span| {
+                        format!(
+                            "{{\"start\":{},\"end\":{},\"text\":{}}}",
+                            span.start,
+                            span.end,
+                            string(&found.line[span.clone()])
+                        )
+                    })
+                    .collect();
+                write!(f, ",\"submatches\":[{}]", submatches.join(","))?;
+            }
+            Event::Context {
+                path,
+                line_number,
+                byte_offset,
+                line,
+            } => write!(
+                f,
+                ",\"path\":{},\"line_number\":{line_number},\"byte_offset\":{byte_offset},\"line\":{}",
+                string(path),
+                string(line)
+            )?,
+            Event::End { path, matches } => {
+                write!(f, ",\"path\":{},\"matches\":{matches}", string(path))?
+            }
+            Event::Summary { files, matches } => {
+                write!(f, ",\"files\":{files},\"matches\":{matches}")?
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+/// `text` as a JSON string, in quotes.
+fn string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events() {
+        let found = Match {
+            line: "fő: \"rust\"",
+            line_number: 2,
+            byte_offset: 5,
+            span: 6..10,
+        };
+
+        assert_eq!(
+            "{\"type\":\"begin\",\"version\":1,\"path\":\"poem.txt\"}",
+            Event::Begin { path: "poem.txt" }.to_string()
+        );
+        assert_eq!(
+            concat!(
+                "{\"type\":\"match\",\"version\":1,\"path\":\"poem.txt\",\"line_number\":2,",
+                "\"byte_offset\":5,\"line\":\"fő: \\\"rust\\\"\",",
+                "\"submatches\":[{\"start\":6,\"end\":10,\"text\":\"rust\"}]}"
+            ),
+            Event::Match {
+                path: "poem.txt",
+                found: &found,
+                submatches: std::slice::from_ref(&found.span),
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "{\"type\":\"summary\",\"version\":1,\"files\":2,\"matches\":0}",
+            Event::Summary {
+                files: 2,
+                matches: 0
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!("\"a\\\\b\\tc\\u0007\"", string("a\\b\tc\u{7}"));
+        assert_eq!("\"ő\"", string("ő"));
+    }
+}
@@ -1,156 +1,545 @@
-use glob::glob;
+use json::Event;
 use std::env;
 use std::error::Error;
-use std::fs;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
 use std::path::PathBuf;
 
-#[derive(Debug, PartialEq)]
+mod args;
+mod color;
+mod discover;
+mod error;
+pub mod json;
+mod matcher;
+mod search;
+mod settings;
+
+pub use args::{help, usage, ArgError};
+pub use color::{paint, use_color, ColorChoice, Colors};
+pub use discover::{discover_files, Ambiguity};
+pub use error::MinigrepError;
+pub use matcher::{Fixed, Folding, Invert, Matcher, Regex, Spans, WholeLine, WholeWord};
+pub use search::{contains_match, search, Line, LineReader, Match};
+pub use settings::{config_path, Setting, Settings, Source};
+
+/// The path that means stdin, also searched when there are no paths.
+pub const STDIN: &str = "-";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Flags {
     Help,
     Version,
     IgnoreCase,
+    NoIgnoreCase,
     Debug,
+    ExtendedRegexp,
+    WordRegexp,
+    LineRegexp,
+    InvertMatch,
+    Count,
+    FilesWithMatches,
+    FilesWithoutMatch,
+    Quiet,
+    FirstFile,
+    AllFiles,
+    UniqueFile,
+    LineNumber,
+    NoLineNumber,
+    ByteOffset,
+    Column,
+    NoColumn,
+    AfterContext(usize),
+    BeforeContext(usize),
+    Context(usize),
+    Color(ColorChoice),
+    CaseFolding(Folding),
+    ShowConfig,
+    Json,
+}
+
+/// The environment variable with the colors, see [`Colors::parse`].
+pub const COLORS_VAR: &str = "MINIGREP_COLORS";
+
+/// What to print about the selected lines.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Output {
+    /// The lines, the default.
+    Lines,
+    Count,
+    FilesWithMatches,
+    FilesWithoutMatch,
+    /// Nothing, only whether a line was selected.
+    Quiet,
+    /// The lines as JSON, see [`json`].
+    Json,
 }
 
-#[derive(Debug)]
-pub struct Config<'a> {
-    pub query: &'a str,
-    pub file_path: &'a str,
+/// A search: what to look for, where and how. Build it from the arguments of
+/// the program with [`Config::build`], or from code:
+///
+/// ```
+/// use minigrep::{Config, Flags};
+///
+/// let config = Config::new("to").path("poem.txt").flag(Flags::IgnoreCase);
+/// assert!(config.settings.ignore_case.value);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Config {
+    pub query: String,
+    pub paths: Vec<String>,
     pub flags: Vec<Flags>,
+    /// The flags on top of the defaults, until [`run`] loads the config file
+    /// and the environment under them.
+    pub settings: Settings,
 }
 
-impl Config<'_> {
-    pub fn build<'b>(args: &'b [String]) -> Result<Config<'b>, &'static str> {
-        if args.len() < 2 {
-            return Err("Expected at least 1 argument");
-        }
-
-        let mut flags = Vec::new();
-        let mut query = None;
-        let mut file_path = None;
-
-        for arg in &args[1..] {
-            match arg.as_str() {
-                "-h" | "--help" => flags.push(Flags::Help),
-                "-v" | "--version" => flags.push(Flags::Version),
-                "-i" | "--ignore-case" => flags.push(Flags::IgnoreCase),
-                "-d" | "--debug" => flags.push(Flags::Debug),
-                _ => {
-                    if query.is_none() {
-                        query = Some(arg);
-                    } else if file_path.is_none() {
-                        file_path = Some(arg);
-                    } else {
-                        return Err("Unexpected argument");
-                    }
-                }
-            }
+impl Config {
+    /// A config from the arguments of the program, like [`env::args`]: flags,
+    /// the query and then paths. The first argument is the name of the
+    /// program.
+    pub fn build(args: impl Iterator<Item = String>) -> Result<Config, ArgError> {
+        let args: Vec<String> = args.skip(1).collect();
+        let (flags, positionals) = args::parse(args.iter().map(String::as_str))?;
+        let mut positionals = positionals.into_iter().map(str::to_string);
+        let query = positionals.next();
+        let paths = positionals.collect();
+
+        // Only the flags that print something instead of searching need no query
+        let no_search = [Flags::Help, Flags::Version, Flags::ShowConfig];
+        if query.is_none() && !flags.iter().any(|flag| no_search.contains(flag)) {
+            return Err(ArgError::MissingQuery);
         }
 
-        if query.is_none() || file_path.is_none() {
-            if flags.is_empty() {
-                return Err("Missing required arguments");
-            } else {
-                return Ok(Config {
-                    query: "",
-                    file_path: "",
-                    flags,
-                });
+        Ok(Config {
+            query: query.unwrap_or_default(),
+            paths,
+            settings: Settings::from_flags(&flags),
+            flags,
+        })
+    }
+
+    /// A search for `query` in stdin, with the default settings.
+    pub fn new(query: impl Into<String>) -> Config {
+        Config {
+            query: query.into(),
+            paths: Vec::new(),
+            flags: Vec::new(),
+            settings: Settings::default(),
+        }
+    }
+
+    /// Searches `path` as well, see [`discover_files`].
+    pub fn path(mut self, path: impl Into<String>) -> Config {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Adds `flag` as if it came after the other flags.
+    pub fn flag(mut self, flag: Flags) -> Config {
+        self.flags.push(flag);
+        self.settings = Settings::from_flags(&self.flags);
+        self
+    }
+
+    /// What to print. `-q` wins over the others, otherwise the last of `-c`,
+    /// `-l`, `-L` and `--json` wins.
+    pub fn output(&self) -> Output {
+        if self.flags.contains(&Flags::Quiet) {
+            return Output::Quiet;
+        }
+
+        self.flags
+            .iter()
+            .rev()
+            .find_map(|flag| match flag {
+                Flags::Count => Some(Output::Count),
+                Flags::FilesWithMatches => Some(Output::FilesWithMatches),
+                Flags::FilesWithoutMatch => Some(Output::FilesWithoutMatch),
+                Flags::Json => Some(Output::Json),
+                _ => None,
+            })
+            .unwrap_or(Output::Lines)
+    }
+
+    /// The matcher for the query, picked by the `-E`, `-w`, `-x` and `-v`
+    /// flags and the `ignore-case` and `case-folding` settings.
+    pub fn matcher(&self) -> Result<Box<dyn Matcher>, regex::Error> {
+        let ignore_case = self.settings.ignore_case.value;
+        let whole_line = self.flags.contains(&Flags::LineRegexp);
+        let whole_word = self.flags.contains(&Flags::WordRegexp);
+
+        let matcher: Box<dyn Matcher> = if self.flags.contains(&Flags::ExtendedRegexp) {
+            // The leftmost match is not always the longest, `a|ab` on `ab`, so
+            // another alternative may be the one that is a whole line or word
+            let pattern = match (whole_line, whole_word) {
+                (true, _) => format!("^(?:{})$", self.query),
+                (false, true) => format!(r"\b{{start-half}}(?:{})\b{{end-half}}", self.query),
+                (false, false) => self.query.clone(),
+            };
+            Box::new(Regex::new(&pattern, ignore_case)?)
+        } else {
+            let fixed = Fixed::new(&self.query, false);
+            let fixed: Box<dyn Matcher> = match ignore_case {
+                true => Box::new(fixed.folding(self.settings.case_folding.value)),
+                false => Box::new(fixed),
+            };
+
+            match (whole_line, whole_word) {
+                (true, _) => Box::new(WholeLine(fixed)),
+                (false, true) => Box::new(WholeWord(fixed)),
+                (false, false) => fixed,
             }
+        };
+
+        if self.flags.contains(&Flags::InvertMatch) {
+            Ok(Box::new(Invert(matcher)))
+        } else {
+            Ok(matcher)
         }
+    }
 
-        return Ok(Config {
-            query: query.unwrap(),
-            file_path: file_path.unwrap(),
-            flags,
-        });
+    /// The colors to print with, [`Colors::none`] unless the `color` setting
+    /// and the terminal allow them.
+    pub fn colors(&self) -> Colors {
+        let choice = self.settings.color.value;
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        if use_color(choice, no_color, io::stdout().is_terminal()) {
+            Colors::parse(&env::var(COLORS_VAR).unwrap_or_default())
+        } else {
+            Colors::none()
+        }
+    }
+
+    /// A line as printed, with the positions asked for with `-n`, `--column`
+    /// and `-b`, like `src/lib.rs:12:5:301:text`. `--column` also prints the
+    /// line number, so editors can jump to `path:line:col:`.
+    ///
+    /// Like in grep, matches are followed by `:` and context lines by `-`.
+    fn format_line(
+        &self,
+        file: &str,
+        line: &Line,
+        matcher: &impl Matcher,
+        colors: &Colors,
+    ) -> String {
+        let column = self.settings.column.value;
+        let separator = match line {
+            Line::Match(_) => paint(&colors.separator, ":"),
+            Line::Context { .. } => paint(&colors.separator, "-"),
+        };
+        let mut formatted = paint(&colors.file, file) + &separator;
+
+        if column || self.settings.line_number.value {
+            formatted += &paint(&colors.line_number, &line.line_number().to_string());
+            formatted += &separator;
+        }
+        if let (true, Line::Match(found)) = (column, line) {
+            formatted += &paint(&colors.column, &found.column().to_string());
+            formatted += &separator;
+        }
+        if self.flags.contains(&Flags::ByteOffset) {
+            formatted += &paint(&colors.byte_offset, &line.byte_offset().to_string());
+            formatted += &separator;
+        }
+
+        match line {
+            Line::Match(found) => {
+                formatted + &colors.highlight(found.line, matcher.spans(found.line))
+            }
+            Line::Context { line, .. } => formatted + line,
+        }
     }
 }
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let mut case_sensitive = true;
+/// Searches the files, returns whether a line was selected, or with `-L`,
+/// whether a file was printed.
+pub fn run(mut config: Config) -> Result<bool, MinigrepError> {
     let mut print_help = false;
     let mut print_version = false;
 
-    for flag in config.flags {
+    for flag in &config.flags {
         match flag {
             Flags::Help => print_help = true,
             Flags::Version => print_version = true,
-            Flags::IgnoreCase => case_sensitive = false,
             _ => (),
         }
     }
 
     if print_help {
-        println!("Usage: minigrep <query> <file>");
-        println!();
-        println!("Options:");
-        println!("  -h, --help         Prints help information");
-        println!("  -v, --version      Prints version information");
-        println!("  -i, --ignore-case  Case insensitive search");
-        println!("  -d, --debug        Debug mode");
-        return Ok(());
+        return writeln!(io::stdout(), "{}", help())
+            .map_or_else(|e| write_failed(e, true), |()| Ok(true));
     }
 
     if print_version {
-        println!("minigrep {}", env!("CARGO_PKG_VERSION"));
-        return Ok(());
+        let version = env!("CARGO_PKG_VERSION");
+        return writeln!(io::stdout(), "minigrep {version}")
+            .map_or_else(|e| write_failed(e, true), |()| Ok(true));
     }
 
-    let file_path = discover_file(config.file_path)?;
-
-    let contents = fs::read_to_string(file_path)?;
+    config.settings = Settings::load(&config.flags, |var| env::var(var).ok())?;
 
-    println!("🔎 Searching for \"{}\"", config.query);
-    println!("File: {}", config.file_path);
-
-    for line in search(&config.query, &contents, case_sensitive) {
-        println!("{line}");
+    if config.flags.contains(&Flags::ShowConfig) {
+        let settings = &config.settings;
+        return write!(io::stdout(), "{settings}")
+            .map_or_else(|e| write_failed(e, true), |()| Ok(true));
     }
 
-    Ok(())
+    let matcher = config.matcher()?;
+    run_with(config, matcher)
 }
 
-fn discover_file(file_path: &str) -> Result<PathBuf, Box<dyn Error>> {
-    let file_path_pattern = format!("{}/**/{}", env::current_dir()?.display(), file_path);
-    let mut file_paths = Vec::new();
+/// Like [`run`], but searches with any [`Matcher`].
+///
+/// Like grep, a path that cannot be searched does not stop the search: its
+/// error is returned once the other paths are searched, and the errors before
+/// it are printed with [`report`].
+pub fn run_with(config: Config, matcher: impl Matcher) -> Result<bool, MinigrepError> {
+    let root = env::current_dir()?;
+    let ambiguity = config.settings.files.value;
+
+    let paths = match config.paths.is_empty() {
+        true => vec![STDIN],
+        false => config.paths.iter().map(String::as_str).collect(),
+    };
+    // Found one path at a time, with whether the file was given by path and
+    // not found in a directory
+    let files = paths.into_iter().flat_map(|path| {
+        let named = path == STDIN || root.join(path).is_file();
+        let found = match path {
+            STDIN => Ok(vec![PathBuf::from(STDIN)]),
+            path => discover_files(&root, path, ambiguity),
+        };
 
-    for entry in glob(&file_path_pattern)? {
-        match entry {
-            Ok(path) => file_paths.push(path),
-            Err(e) => return Err(Box::new(e)),
+        match found {
+            Ok(found) => found.into_iter().map(|file| Ok((file, named))).collect(),
+            Err(e) => vec![Err(e)],
         }
-    }
+    });
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut failed = None;
 
-    if file_paths.is_empty() {
-        return Err(format!("File not found: {}", file_path).into());
+    let output = config.output();
+    let colors = config.colors();
+    let mut stdout = io::stdout().lock();
+    let mut found = false;
+    // The files searched and the selected lines in all of them, for `--json`
+    let mut searched_files = 0;
+    let mut total = 0;
+
+    // On stderr, so that stdout is only `path:line:col:` lines for editors
+    if output == Output::Lines {
+        eprintln!("🔎 Searching for \"{}\"", config.query);
     }
 
-    Ok(file_paths[0].clone())
-}
+    let before = config.settings.before_context.value;
+    let after = config.settings.after_context.value;
+    // The file and line number of the last line printed, to separate groups
+    let mut last = None;
 
-pub fn search<'a>(query: &str, contents: &'a str, case_sensitive: bool) -> Vec<&'a str> {
-    let mut results = Vec::new();
+    for file in files {
+        let (file, named) = match file {
+            Ok(file) => file,
+            Err(e) => {
+                fail(&mut failed, e);
+                continue;
+            }
+        };
+        if seen.contains(&file) {
+            continue;
+        }
+        seen.push(file.clone());
+        let index = seen.len();
 
-    for line in contents.lines() {
-        if case_sensitive {
-            if line.contains(&query) {
-                results.push(line);
+        let reader: Box<dyn BufRead> = match file.to_str() {
+            Some(STDIN) => Box::new(io::stdin().lock()),
+            _ => match File::open(&file) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(e) => {
+                    fail(&mut failed, MinigrepError::io(&file, e));
+                    continue;
+                }
+            },
+        };
+        searched_files += 1;
+        let name = match file.to_str() {
+            Some(STDIN) => String::from("(standard input)"),
+            _ => file.display().to_string(),
+        };
+
+        let searched = match output {
+            Output::Lines => search(&matcher, reader, before, after, |line| {
+                // Groups are only separated when there is context to tell apart
+                let next = (index, line.line_number());
+                if (before > 0 || after > 0) && last.is_some_and(|(i, n)| (i, n + 1) != next) {
+                    writeln!(stdout, "{}", paint(&colors.separator, "--"))?;
+                }
+                last = Some(next);
+                found |= matches!(line, Line::Match(_));
+
+                let line = config.format_line(&name, &line, &matcher, &colors);
+                writeln!(stdout, "{line}").map(|()| true)
+            }),
+            Output::Count => {
+                let mut count = 0;
+                let searched = search(&matcher, reader, 0, 0, |_| {
+                    count += 1;
+                    Ok(true)
+                });
+                found |= count > 0;
+                let separator = paint(&colors.separator, ":");
+                let name = paint(&colors.file, &name);
+                searched.and_then(|()| writeln!(stdout, "{name}{separator}{count}"))
             }
-        } else {
-            if line.to_lowercase().contains(&query.to_lowercase()) {
-                results.push(line);
+            Output::FilesWithMatches | Output::FilesWithoutMatch => {
+                contains_match(&matcher, reader).and_then(|matched| {
+                    if matched != (output == Output::FilesWithMatches) {
+                        return Ok(());
+                    }
+                    found = true;
+                    writeln!(stdout, "{}", paint(&colors.file, &name))
+                })
+            }
+            Output::Quiet => contains_match(&matcher, reader).map(|matched| found |= matched),
+            Output::Json => {
+                let path = file.display().to_string();
+                let mut matches = 0;
+                let begun = writeln!(stdout, "{}", Event::Begin { path: &path });
+
+                let searched = begun.and_then(|()| {
+                    search(&matcher, reader, before, after, |line| {
+                        let written = match line {
+                            Line::Match(found) => {
+                                matches += 1;
+                                let submatches: Vec<_> = matcher
+                                    .spans(found.line)
+                                    .filter(|span| !span.is_empty())
+                                    .collect();
+                                let event = Event::Match {
+                                    path: &path,
+                                    found: &found,
+                                    submatches: &submatches,
+                                };
+                                writeln!(stdout, "{event}")
+                            }
+                            Line::Context {
+                                line,
+                                line_number,
+                                byte_offset,
+                            } => {
+                                let event = Event::Context {
+                                    path: &path,
+                                    line_number,
+                                    byte_offset,
+                                    line,
+                                };
+                                writeln!(stdout, "{event}")
+                            }
+                        };
+                        written.map(|()| true)
+                    })
+                });
+
+                found |= matches > 0;
+                total += matches;
+                let ended = writeln!(
+                    stdout,
+                    "{}",
+                    Event::End {
+                        path: &path,
+                        matches
+                    }
+                );
+                searched.and(ended)
+            }
+        };
+
+        match searched {
+            Ok(()) => (),
+            // Directories often have binary files in them, like images
+            Err(e) if e.kind() == io::ErrorKind::InvalidData && !named => {
+                eprintln!("Skipping {}: not UTF-8", file.display());
             }
+            // Writing failed, not reading, so nothing more can be printed
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                return failed.map_or(Ok(found), Err);
+            }
+            Err(e) => fail(&mut failed, MinigrepError::io(&name, e)),
+        }
+
+        // The other files cannot change the answer
+        if output == Output::Quiet && found {
+            if let Some(e) = failed {
+                report(&e);
+            }
+            return Ok(true);
         }
     }
 
-    return results;
+    if output == Output::Json {
+        let summary = Event::Summary {
+            files: searched_files,
+            matches: total,
+        };
+        if let Err(e) = writeln!(stdout, "{summary}") {
+            write_failed(e, found)?;
+        }
+    }
+
+    failed.map_or(Ok(found), Err)
+}
+
+/// Keeps `e` to return after the search, and prints the error it replaces.
+fn fail(failed: &mut Option<MinigrepError>, e: MinigrepError) {
+    if let Some(previous) = failed.replace(e) {
+        report(&previous);
+    }
+}
+
+/// Prints `e` and what caused it on stderr.
+pub fn report(e: &MinigrepError) {
+    eprintln!("Application error: {e}");
+    let mut source = e.source();
+    while let Some(e) = source {
+        eprintln!("  caused by: {e}");
+        source = e.source();
+    }
+}
+
+/// What to do when writing the output fails. Like grep, minigrep stops quietly
+/// when whoever reads it is gone, like `head -1`, so `found` is the answer.
+fn write_failed(e: io::Error, found: bool) -> Result<bool, MinigrepError> {
+    match e.kind() {
+        io::ErrorKind::BrokenPipe => Ok(found),
+        _ => Err(e.into()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn lines(matcher: &impl Matcher, contents: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        search(matcher, contents.as_bytes(), 0, 0, |line| {
+            lines.push(line.text().to_string());
+            Ok(true)
+        })
+        .unwrap();
+
+        lines
+    }
+
+    fn found(line: &str, span: std::ops::Range<usize>) -> Line<'_> {
+        Line::Match(Match {
+            line,
+            line_number: 2,
+            byte_offset: 5,
+            span,
+        })
+    }
+
     #[test]
     fn case_sensitive() {
         let query = "duct";
@@ -162,10 +551,79 @@ Duct tape.";
 
         assert_eq!(
             vec!["safe, fast, productive."],
-            search(query, contents, true)
+            lines(&Fixed::new(query, false), contents)
+        );
+    }
+
+    #[test]
+    fn whole_line_regex() {
+        let args = ["minigrep", "-E", "-x", "a|ab", "poem.txt"].map(String::from);
+        let matcher = Config::build(args.into_iter()).unwrap().matcher().unwrap();
+
+        assert_eq!(vec!["ab", "a"], lines(&matcher, "ab\nabc\na"));
+    }
+
+    #[test]
+    fn whole_word_regex() {
+        let args = ["minigrep", "-E", "-w", "rust|rusty", "poem.txt"].map(String::from);
+        let matcher = Config::build(args.into_iter()).unwrap().matcher().unwrap();
+
+        assert_eq!(
+            vec!["rusty", "a rust", "@rust"],
+            lines(&matcher, "rusty\na rust\nrustacean\n@rust")
+        );
+        assert_eq!(Some(0..5), matcher.find_at("rusty", 0));
+
+        let args = ["minigrep", "-E", "-w", "@rust", "poem.txt"].map(String::from);
+        let matcher = Config::build(args.into_iter()).unwrap().matcher().unwrap();
+        assert_eq!(vec!["a @rust"], lines(&matcher, "a @rust\nb@rust"));
+    }
+
+    #[test]
+    fn case_folding() {
+        let args = ["minigrep", "-i", "STRASSE", "--case-folding=simple"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+
+        assert_eq!(Folding::Simple, config.settings.case_folding.value);
+        assert_eq!(
+            vec!["strasse"],
+            lines(&config.matcher().unwrap(), "straße\nstrasse")
+        );
+
+        let args = ["minigrep", "-i", "STRASSE"].map(String::from);
+        let matcher = Config::build(args.into_iter()).unwrap().matcher().unwrap();
+        assert_eq!(
+            vec!["straße", "strasse"],
+            lines(&matcher, "straße\nstrasse")
         );
     }
 
+    #[test]
+    fn many_paths() {
+        let args = [
+            "minigrep",
+            "--unique-file",
+            "to",
+            "poem.txt",
+            "src",
+            "--first-file",
+        ]
+        .map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+
+        assert_eq!("to", config.query);
+        assert_eq!(vec!["poem.txt", "src"], config.paths);
+        assert_eq!(
+            Config::new("to")
+                .flag(Flags::UniqueFile)
+                .path("poem.txt")
+                .path("src")
+                .flag(Flags::FirstFile),
+            config
+        );
+        assert_eq!(Ambiguity::First, config.settings.files.value);
+    }
+
     #[test]
     fn case_insensitive() {
         let query = "rUsT";
@@ -175,6 +633,159 @@ safe, fast, productive.
 Pick three.
 Trust me.";
 
-        assert_eq!(vec!["Rust:", "Trust me."], search(query, contents, false));
+        assert_eq!(
+            vec!["Rust:", "Trust me."],
+            lines(&Fixed::new(query, true), contents)
+        );
+    }
+
+    #[test]
+    fn format_line() {
+        let args = ["minigrep", "--column", "-b", "rust", "poem.txt"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        let file = "poem.txt";
+        let context = Line::Context {
+            line: "Rust",
+            line_number: 1,
+            byte_offset: 0,
+        };
+
+        let matcher = Fixed::new("rust", false);
+        let none = Colors::none();
+
+        assert_eq!(
+            "poem.txt:2:5:5:fő: rust",
+            config.format_line(file, &found("fő: rust", 5..9), &matcher, &none)
+        );
+        assert_eq!(
+            "poem.txt-1-0-Rust",
+            config.format_line(file, &context, &matcher, &none)
+        );
+    }
+
+    #[test]
+    fn format_line_in_color() {
+        let args = ["minigrep", "-n", "--color=always", "rust", "poem.txt"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        let matcher = Fixed::new("rust", false);
+        let colors = Colors::parse("fn=34:ln=:se=");
+
+        assert_eq!(
+            "\x1b[34mpoem.txt\x1b[0m:2:a \x1b[01;31mrust\x1b[0m",
+            config.format_line("poem.txt", &found("a rust", 2..6), &matcher, &colors)
+        );
+        assert_eq!(
+            Some(&Flags::Color(ColorChoice::Always)),
+            config.flags.last()
+        );
+
+        let args = ["minigrep", "--color", "sometimes", "rust", "poem.txt"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn invert_and_output_modes() {
+        let args = ["minigrep", "-l", "-v", "-c", "rust", "poem.txt"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+        let matcher = config.matcher().unwrap();
+
+        assert_eq!(Output::Count, config.output());
+        assert_eq!(
+            vec!["Rust", "Go", ""],
+            lines(&matcher, "Rust\nGo\nrust\n\n")
+        );
+        assert!(!contains_match(&matcher, "rust\nrusty".as_bytes()).unwrap());
+
+        let args = ["minigrep", "-q", "-L", "rust", "poem.txt"].map(String::from);
+        assert_eq!(
+            Output::Quiet,
+            Config::build(args.into_iter()).unwrap().output()
+        );
+
+        let config = Config::new("rust").flag(Flags::Count).flag(Flags::Json);
+        assert_eq!(Output::Json, config.output());
+    }
+
+    #[test]
+    fn missing_paths() {
+        let file = env::temp_dir().join(format!("minigrep-paths-{}.txt", std::process::id()));
+        std::fs::write(&file, "rust\n").unwrap();
+        let path = file.display().to_string();
+
+        // Found after a missing path
+        let config = Config::new("rust")
+            .path("missing.txt")
+            .path(&path)
+            .flag(Flags::Quiet);
+        assert!(matches!(
+            run_with(config, Fixed::new("rust", false)),
+            Ok(true)
+        ));
+
+        // The last error, once every path is searched
+        let config = Config::new("go")
+            .path("missing.txt")
+            .path(&path)
+            .path("gone.txt")
+            .flag(Flags::Quiet);
+        assert!(matches!(
+            run_with(config, Fixed::new("go", false)),
+            Err(MinigrepError::FileNotFound(path)) if path.ends_with("gone.txt")
+        ));
+
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn broken_pipe() {
+        let gone = io::Error::from(io::ErrorKind::BrokenPipe);
+        assert!(matches!(write_failed(gone, true), Ok(true)));
+
+        let full = io::Error::from(io::ErrorKind::StorageFull);
+        assert!(matches!(
+            write_failed(full, true),
+            Err(MinigrepError::Io { path: None, .. })
+        ));
+    }
+
+    #[test]
+    fn context_flags() {
+        let args = [
+            "minigrep",
+            "-B1",
+            "--context",
+            "3",
+            "--after-context=2",
+            "a",
+            "b",
+        ]
+        .map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+
+        let settings = &config.settings;
+        assert_eq!(
+            (1, 2),
+            (settings.before_context.value, settings.after_context.value)
+        );
+        assert_eq!(vec!["b"], config.paths);
+
+        let args = ["minigrep", "-n", "a"].map(String::from);
+        assert!(Config::build(args.into_iter()).unwrap().paths.is_empty());
+
+        let args = ["minigrep", "-A", "x", "a", "b"].map(String::from);
+        assert!(Config::build(args.into_iter()).is_err());
+    }
+
+    #[test]
+    fn missing_query() {
+        for flag in ["-i", "-v", "--json"] {
+            let args = ["minigrep", flag].map(String::from);
+            assert_eq!(Err(ArgError::MissingQuery), Config::build(args.into_iter()));
+        }
+
+        for flag in ["-h", "--version", "--show-config"] {
+            let args = ["minigrep", "-i", flag].map(String::from);
+            assert_eq!("", Config::build(args.into_iter()).unwrap().query);
+        }
     }
 }
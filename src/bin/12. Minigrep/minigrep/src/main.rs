@@ -2,20 +2,28 @@ use minigrep::{Config, Flags};
 use std::env;
 use std::process;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+/// Like grep, so scripts can tell "no match" from "failed": `if minigrep -q`
+/// is only false when nothing matched.
+const NOT_FOUND: i32 = 1;
+const FAILED: i32 = 2;
 
-    let config = Config::build(&args).unwrap_or_else(|err| {
+fn main() {
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
         eprintln!("{err}");
-        process::exit(1);
+        eprintln!("{}", minigrep::usage());
+        process::exit(FAILED);
     });
 
     if config.flags.iter().any(|flag| flag == &Flags::Debug) {
         dbg!(&config);
     }
 
-    if let Err(e) = minigrep::run(config) {
-        eprintln!("Application error: {e}");
-        process::exit(1);
+    match minigrep::run(config) {
+        Ok(true) => (),
+        Ok(false) => process::exit(NOT_FOUND),
+        Err(e) => {
+            minigrep::report(&e);
+            process::exit(FAILED);
+        }
     }
 }
@@ -0,0 +1,470 @@
+use std::ops::Range;
+
+/// Finds the parts of a line that match a query.
+///
+/// Matches are byte ranges into the line, so they can be highlighted or
+/// reported with their position, not just counted.
+pub trait Matcher {
+    /// The leftmost match that starts at or after `start`. `start` is always
+    /// on a char boundary.
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>>;
+
+    fn is_match(&self, line: &str) -> bool {
+        self.find_at(line, 0).is_some()
+    }
+
+    /// Every match in `line`, left to right and without overlaps.
+    fn spans<'m, 'l>(&'m self, line: &'l str) -> Spans<'m, 'l, Self>
+    where
+        Self: Sized,
+    {
+        Spans {
+            matcher: self,
+            line,
+            start: 0,
+        }
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for &M {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        (**self).find_at(line, start)
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for Box<M> {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        (**self).find_at(line, start)
+    }
+}
+
+/// The iterator returned by [`Matcher::spans`].
+pub struct Spans<'m, 'l, M> {
+    matcher: &'m M,
+    line: &'l str,
+    start: usize,
+}
+
+impl<M: Matcher> Iterator for Spans<'_, '_, M> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start > self.line.len() {
+            return None;
+        }
+
+        let span = self.matcher.find_at(self.line, self.start)?;
+        // An empty match would be found again at the same place
+        self.start = if span.is_empty() {
+            next_char(self.line, span.end)
+        } else {
+            span.end
+        };
+
+        Some(span)
+    }
+}
+
+/// How `-i` compares chars, `--case-folding`.
+///
+/// A char folds to its uppercase lowercased, which gives the same result as
+/// Unicode's `CaseFolding.txt` without a table. The Turkish
+/// `I` rules are not used, so `İ` folds to `i̇`, not to `i`, and `ı` folds to
+/// itself.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum Folding {
+    /// A char folds to one char, so `ß` only matches `ß` and `ẞ`.
+    Simple,
+    /// A char can fold to several, so `ß` also matches `ss` and `SS`.
+    #[default]
+    Full,
+}
+
+impl Folding {
+    pub fn parse(value: &str) -> Option<Folding> {
+        match value {
+            "simple" => Some(Folding::Simple),
+            "full" => Some(Folding::Full),
+            _ => None,
+        }
+    }
+
+    /// The chars that `c` folds to, without allocating.
+    pub fn fold(self, c: char) -> impl Iterator<Item = char> {
+        let one = match c {
+            _ if c.is_ascii() => Some(c.to_ascii_lowercase()),
+            _ if self == Folding::Full && expands(c) => None,
+            _ => match lowercase_exception(c) {
+                Some(folded) => Some(folded),
+                None => {
+                    // Only `İ` lowercases to more than one char, `i̇`
+                    let mut lower = c.to_lowercase();
+                    match (lower.len(), self) {
+                        (1, _) => lower.next(),
+                        (_, Folding::Simple) => Some(c),
+                        (_, Folding::Full) => None,
+                    }
+                }
+            },
+        };
+        // Uppercasing is slow, so it is only done when lowercasing is not enough
+        let many = one
+            .is_none()
+            .then(|| c.to_uppercase().flat_map(char::to_lowercase));
+
+        one.into_iter().chain(many.into_iter().flatten())
+    }
+}
+
+/// Whether `c` uppercases to several chars, like `ß` to `SS`, with some false
+/// positives in the Greek Extended block.
+fn expands(c: char) -> bool {
+    matches!(
+        c as u32,
+        0xDF | 0x149 | 0x1F0 | 0x390 | 0x3B0 | 0x587 | 0x1E96..=0x1E9A | 0x1F50..=0x1FFC | 0xFB00..=0xFB17
+    )
+}
+
+/// Whether the folding of `c` may start with an ASCII char, like `ﬁ` and
+/// the Kelvin sign `K`. Most text can then be skipped without folding it.
+fn may_fold_to_ascii(c: char) -> bool {
+    c.is_ascii() || matches!(c, 'ſ' | '\u{212A}' | 'İ') || expands(c)
+}
+
+/// The chars whose folding is not their lowercase, like `ς` that folds to the
+/// same `σ` as `Σ`.
+fn lowercase_exception(c: char) -> Option<char> {
+    let folded = match c {
+        'µ' => 'μ',
+        'ſ' => 's',
+        '\u{345}' | '\u{1FBE}' => 'ι',
+        'ς' => 'σ',
+        'ϐ' => 'β',
+        'ϑ' => 'θ',
+        'ϕ' => 'φ',
+        'ϖ' => 'π',
+        'ϰ' => 'κ',
+        'ϱ' => 'ρ',
+        'ϵ' => 'ε',
+        'ẛ' => 'ṡ',
+        // Old Cyrillic letter forms
+        '\u{1C80}' => 'в',
+        '\u{1C81}' => 'д',
+        '\u{1C82}' => 'о',
+        '\u{1C83}' => 'с',
+        '\u{1C84}' | '\u{1C85}' => 'т',
+        '\u{1C86}' => 'ъ',
+        '\u{1C87}' => 'ѣ',
+        '\u{1C88}' => 'ꙋ',
+        _ => return None,
+    };
+
+    Some(folded)
+}
+
+/// Matches the query as it is, the default.
+pub struct Fixed {
+    query: String,
+    /// The query folded once, `None` when the case matters.
+    folded: Option<Vec<char>>,
+    folding: Folding,
+}
+
+impl Fixed {
+    pub fn new(query: &str, ignore_case: bool) -> Fixed {
+        let fixed = Fixed {
+            query: query.to_string(),
+            folded: None,
+            folding: Folding::default(),
+        };
+
+        if ignore_case {
+            fixed.folding(Folding::default())
+        } else {
+            fixed
+        }
+    }
+
+    /// Ignores the case with `folding`.
+    pub fn folding(self, folding: Folding) -> Fixed {
+        let folded = self.query.chars().flat_map(|c| folding.fold(c)).collect();
+
+        Fixed {
+            folded: Some(folded),
+            folding,
+            ..self
+        }
+    }
+
+    /// The length of the prefix of `text` that folds to the folded query. A
+    /// char of `text` must match as a whole, so `s` does not match the first
+    /// half of `ß`.
+    fn folded_len(&self, text: &str, folded: &[char]) -> Option<usize> {
+        let mut query = folded.iter();
+
+        for (index, c) in text.char_indices() {
+            if c.is_ascii() {
+                if query.next() != Some(&c.to_ascii_lowercase()) {
+                    return None;
+                }
+            } else {
+                for c in self.folding.fold(c) {
+                    if query.next() != Some(&c) {
+                        return None;
+                    }
+                }
+            }
+            if query.len() == 0 {
+                return Some(index + c.len_utf8());
+            }
+        }
+
+        None
+    }
+}
+
+impl Matcher for Fixed {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        let Some(folded) = &self.folded else {
+            let index = line[start..].find(&self.query)? + start;
+            return Some(index..index + self.query.len());
+        };
+
+        let Some(&first) = folded.first() else {
+            return Some(start..start);
+        };
+
+        let bytes = line.as_bytes();
+        let mut index = start;
+
+        // Most chars cannot start a match, so they are only looked at once,
+        // and ASCII ones without decoding them
+        while index < bytes.len() {
+            let (starts, len) = match bytes[index] {
+                byte if byte.is_ascii() => (char::from(byte.to_ascii_lowercase()) == first, 1),
+                _ => {
+                    let c = line[index..].chars().next()?;
+                    let folds_to_first = (!first.is_ascii() || may_fold_to_ascii(c))
+                        && self.folding.fold(c).next() == Some(first);
+                    (folds_to_first, c.len_utf8())
+                }
+            };
+
+            if starts {
+                if let Some(len) = self.folded_len(&line[index..], folded) {
+                    return Some(index..index + len);
+                }
+            }
+            index += len;
+        }
+
+        None
+    }
+}
+
+/// Matches a regular expression, `-E`.
+pub struct Regex {
+    regex: regex::Regex,
+}
+
+impl Regex {
+    pub fn new(pattern: &str, ignore_case: bool) -> Result<Regex, regex::Error> {
+        let regex = regex::RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()?;
+
+        Ok(Regex { regex })
+    }
+}
+
+impl Matcher for Regex {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        self.regex.find_at(line, start).map(|m| m.range())
+    }
+}
+
+/// Only keeps the matches that are a whole word, `-w`.
+///
+/// A word is made of letters, digits and `_`, like in grep. Only the next
+/// match is tried when one is not a whole word, so regexes with alternatives
+/// are wrapped in word boundaries instead.
+pub struct WholeWord<M>(pub M);
+
+impl<M: Matcher> Matcher for WholeWord<M> {
+    fn find_at(&self, line: &str, mut start: usize) -> Option<Range<usize>> {
+        loop {
+            let span = self.0.find_at(line, start)?;
+
+            let before = line[..span.start].chars().next_back();
+            let after = line[span.end..].chars().next();
+            if !before.is_some_and(is_word) && !after.is_some_and(is_word) {
+                return Some(span);
+            }
+
+            // A later match may still be a whole word, like `rust` in `rusty rust`
+            start = next_char(line, span.start);
+            if start > line.len() {
+                return None;
+            }
+        }
+    }
+}
+
+/// Only matches lines that match as a whole, `-x`.
+pub struct WholeLine<M>(pub M);
+
+impl<M: Matcher> Matcher for WholeLine<M> {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        match self.0.find_at(line, start) {
+            Some(span) if start == 0 && span == (0..line.len()) => Some(span),
+            _ => None,
+        }
+    }
+}
+
+/// Matches the lines that the other matcher does not, `-v`.
+///
+/// A selected line has an empty match at its start, since there is nothing in
+/// it to highlight.
+pub struct Invert<M>(pub M);
+
+impl<M: Matcher> Matcher for Invert<M> {
+    fn find_at(&self, line: &str, start: usize) -> Option<Range<usize>> {
+        if start == 0 && !self.0.is_match(line) {
+            Some(0..0)
+        } else {
+            None
+        }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The index of the char after the one at `index`, or past the end.
+fn next_char(line: &str, index: usize) -> usize {
+    line[index..]
+        .chars()
+        .next()
+        .map_or(line.len() + 1, |c| index + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(matcher: impl Matcher, line: &str) -> Vec<Range<usize>> {
+        matcher.spans(line).collect()
+    }
+
+    #[test]
+    fn fixed() {
+        assert_eq!(
+            vec![0..4, 18..22],
+            spans(Fixed::new("rust", false), "rust is not Rust, rust")
+        );
+        assert_eq!(
+            vec![0..4, 12..16],
+            spans(Fixed::new("rUsT", true), "Rust is not rust")
+        );
+        assert!(spans(Fixed::new("rust", false), "Rust").is_empty());
+    }
+
+    #[test]
+    fn fixed_ignore_case_keeps_byte_offsets() {
+        // `ő` is two bytes long
+        assert_eq!(vec![4..7], spans(Fixed::new("ŐR", true), "fő őr"));
+    }
+
+    #[test]
+    fn full_case_folding() {
+        let full = Fixed::new("STRASSE", true);
+        let simple = Fixed::new("STRASSE", false).folding(Folding::Simple);
+
+        assert_eq!(vec![4..11], spans(&full, "die straße"));
+        assert!(!simple.is_match("die straße"));
+        assert!(simple.is_match("die strasse"));
+        // The `s` would only match half of the `ß`
+        assert!(!Fixed::new("stras", true).is_match("straße"));
+        assert!(Fixed::new("ΣΟΦΟΣ", true).is_match("σοφος"));
+        assert!(Fixed::new("σοφος", true).is_match("ΣΟΦΟΣ"));
+    }
+
+    #[test]
+    fn folding_matches_its_definition() {
+        for c in (0..=char::MAX as u32).filter_map(char::from_u32) {
+            let full = c.to_uppercase().flat_map(char::to_lowercase);
+            let simple = match full.clone().count() {
+                1 => full.clone().next().unwrap(),
+                _ => c
+                    .to_lowercase()
+                    .next()
+                    .filter(|_| c.to_lowercase().len() == 1)
+                    .unwrap_or(c),
+            };
+            // `ı` uppercases to `I`, but has no folding
+            let (full, simple) = match c {
+                'ı' => (vec![c], c),
+                _ => (full.collect::<Vec<_>>(), simple),
+            };
+
+            assert_eq!(full, Folding::Full.fold(c).collect::<Vec<_>>(), "{c:?}");
+            assert!(!full[0].is_ascii() || may_fold_to_ascii(c), "{c:?}");
+            assert_eq!(
+                vec![simple],
+                Folding::Simple.fold(c).collect::<Vec<_>>(),
+                "{c:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn turkish_i() {
+        let matcher = Fixed::new("İstanbul", true);
+
+        assert!(matcher.is_match("i\u{307}stanbul"));
+        assert!(!matcher.is_match("istanbul"));
+        assert!(!Fixed::new("ısparta", true).is_match("ISPARTA"));
+    }
+
+    #[test]
+    fn regex() {
+        let regex = Regex::new(r"\d+", false).unwrap();
+
+        assert_eq!(vec![4..6, 10..13], spans(regex, "top 10 of 100"));
+    }
+
+    #[test]
+    fn empty_matches_advance() {
+        let regex = Regex::new("x*", false).unwrap();
+
+        assert_eq!(vec![0..0, 1..2, 2..2], spans(regex, "ax"));
+    }
+
+    #[test]
+    fn whole_word() {
+        let matcher = WholeWord(Fixed::new("rust", false));
+
+        assert_eq!(vec![6..10], spans(&matcher, "rusty rust trust"));
+        assert!(!matcher.is_match("rusty_rust"));
+    }
+
+    #[test]
+    fn whole_line() {
+        let matcher = WholeLine(Fixed::new("Pick three.", false));
+
+        assert!(matcher.is_match("Pick three."));
+        assert!(!matcher.is_match("Pick three. Or four."));
+        assert_eq!(vec![0..11], spans(matcher, "Pick three."));
+    }
+
+    #[test]
+    fn invert() {
+        let matcher = Invert(Fixed::new("rust", false));
+
+        assert!(!matcher.is_match("trust"));
+        assert_eq!(vec![0..0], spans(&matcher, "Rust"));
+        assert_eq!(vec![0..0], spans(&matcher, ""));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::Matcher;
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+use std::ops::Range;
+
+/// A line that matches the query.
+#[derive(Debug, PartialEq)]
+pub struct Match<'a> {
+    /// The line, without its line ending.
+    pub line: &'a str,
+    /// 1-based, like in editors.
+    pub line_number: usize,
+    /// Where the line starts in the input, in bytes.
+    pub byte_offset: usize,
+    /// The first match in the line, in bytes.
+    pub span: Range<usize>,
+}
+
+impl Match<'_> {
+    /// The 1-based column of the first match, counted in chars, so `ő` is one
+    /// column even though it is two bytes.
+    pub fn column(&self) -> usize {
+        self.line[..self.span.start].chars().count() + 1
+    }
+}
+
+/// A line to print, a match or one of the lines around it.
+#[derive(Debug, PartialEq)]
+pub enum Line<'a> {
+    Match(Match<'a>),
+    /// A line printed because of `-A`, `-B` or `-C`.
+    Context {
+        line: &'a str,
+        line_number: usize,
+        byte_offset: usize,
+    },
+}
+
+impl<'a> Line<'a> {
+    pub fn text(&self) -> &'a str {
+        match self {
+            Line::Match(found) => found.line,
+            Line::Context { line, .. } => line,
+        }
+    }
+
+    pub fn line_number(&self) -> usize {
+        match self {
+            Line::Match(found) => found.line_number,
+            Line::Context { line_number, .. } => *line_number,
+        }
+    }
+
+    pub fn byte_offset(&self) -> usize {
+        match self {
+            Line::Match(found) => found.byte_offset,
+            Line::Context { byte_offset, .. } => *byte_offset,
+        }
+    }
+}
+
+/// Reads the lines of any [`BufRead`] into the same buffer, so memory stays
+/// flat however large the input is.
+pub struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+    byte_offset: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> LineReader<R> {
+        LineReader {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+            byte_offset: 0,
+        }
+    }
+
+    /// The next line without its line ending, with its 1-based number and
+    /// byte offset. Fails with [`io::ErrorKind::InvalidData`] on a line that
+    /// is not UTF-8.
+    pub fn next_line(&mut self) -> io::Result<Option<(usize, usize, &str)>> {
+        self.buffer.clear();
+        let read = self.reader.read_line(&mut self.buffer)?;
+        if read == 0 {
+            return Ok(None);
+        }
+
+        self.line_number += 1;
+        let start = self.byte_offset;
+        self.byte_offset += read;
+
+        let line = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        Ok(Some((self.line_number, start, line)))
+    }
+}
+
+/// Searches `reader` line by line and gives every match, with `before` and
+/// `after` lines of context, to `sink`. No line is given twice, and a gap in
+/// the line numbers is where a new group of lines starts.
+///
+/// The lines borrow a buffer that is reused for the next line. `sink` returns
+/// whether to keep searching.
+pub fn search<R, M>(
+    matcher: &M,
+    reader: R,
+    before: usize,
+    after: usize,
+    mut sink: impl FnMut(Line) -> io::Result<bool>,
+) -> io::Result<()>
+where
+    R: BufRead,
+    M: Matcher + ?Sized,
+{
+    let mut lines = LineReader::new(reader);
+    // The last lines that were not printed, and the buffers to reuse for them
+    let mut previous: VecDeque<(usize, usize, String)> = VecDeque::with_capacity(before);
+    let mut spare = Vec::with_capacity(before);
+    let mut after_left = 0;
+
+    while let Some((line_number, byte_offset, line)) = lines.next_line()? {
+        if let Some(span) = matcher.find_at(line, 0) {
+            for (line_number, byte_offset, line) in &previous {
+                let context = Line::Context {
+                    line,
+                    line_number: *line_number,
+                    byte_offset: *byte_offset,
+                };
+                if !sink(context)? {
+                    return Ok(());
+                }
+            }
+            spare.extend(previous.drain(..).map(|(_, _, buffer)| buffer));
+
+            let found = Match {
+                line,
+                line_number,
+                byte_offset,
+                span,
+            };
+            if !sink(Line::Match(found))? {
+                return Ok(());
+            }
+            after_left = after;
+        } else if after_left > 0 {
+            after_left -= 1;
+
+            let context = Line::Context {
+                line,
+                line_number,
+                byte_offset,
+            };
+            if !sink(context)? {
+                return Ok(());
+            }
+        } else if before > 0 {
+            let mut buffer = if previous.len() == before {
+                previous.pop_front().map(|(_, _, buffer)| buffer)
+            } else {
+                spare.pop()
+            }
+            .unwrap_or_default();
+            buffer.clear();
+            buffer.push_str(line);
+            previous.push_back((line_number, byte_offset, buffer));
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether any line of `reader` matches, stops reading at the first one that
+/// does.
+pub fn contains_match<R: BufRead, M: Matcher + ?Sized>(matcher: &M, reader: R) -> io::Result<bool> {
+    let mut lines = LineReader::new(reader);
+
+    while let Some((_, _, line)) = lines.next_line()? {
+        if matcher.is_match(line) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fixed;
+
+    /// The groups of lines as line numbers, negative for context lines.
+    fn groups(contents: &str, query: &str, before: usize, after: usize) -> Vec<Vec<isize>> {
+        let mut groups: Vec<Vec<isize>> = Vec::new();
+        let mut last = 0;
+
+        search(
+            &Fixed::new(query, false),
+            contents.as_bytes(),
+            before,
+            after,
+            |line| {
+                if groups.is_empty() || line.line_number() != last + 1 {
+                    groups.push(Vec::new());
+                }
+                last = line.line_number();
+
+                let number = match line {
+                    Line::Match(_) => last as isize,
+                    Line::Context { .. } => -(last as isize),
+                };
+                groups.last_mut().unwrap().push(number);
+                Ok(true)
+            },
+        )
+        .unwrap();
+
+        groups
+    }
+
+    #[test]
+    fn positions() {
+        let contents = "Rust:\r\nfő: rust\nno\nrust";
+        let mut matches = Vec::new();
+
+        search(
+            &Fixed::new("rust", false),
+            contents.as_bytes(),
+            0,
+            0,
+            |line| {
+                if let Line::Match(found) = line {
+                    matches.push((found.line_number, found.byte_offset, found.column()));
+                }
+                Ok(true)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(vec![(2, 7, 5), (4, 20, 1)], matches);
+    }
+
+    #[test]
+    fn separate_groups() {
+        let contents = "a\nb\nx\nc\nd\ne\nf\nx\ng";
+
+        assert_eq!(
+            vec![vec![-2, 3, -4], vec![-7, 8, -9]],
+            groups(contents, "x", 1, 1)
+        );
+        assert_eq!(vec![vec![3], vec![8]], groups(contents, "x", 0, 0));
+    }
+
+    #[test]
+    fn overlapping_windows_merge() {
+        let contents = "x\na\nx\nb\nc\nd\nx";
+
+        assert_eq!(
+            vec![vec![1, -2, 3, -4, -5, -6, 7]],
+            groups(contents, "x", 0, 3)
+        );
+        // Windows are cut at the start and end of the input
+        assert_eq!(vec![vec![-1, 2, -3]], groups(contents, "a", 5, 1));
+        // Touching windows merge as well
+        assert_eq!(
+            vec![vec![3, -4, -5, -6, 7]],
+            groups("a\nb\nx\nc\nd\ne\nx", "x", 0, 3)
+        );
+        assert_eq!(
+            vec![vec![-2, -3, 4, -5, -6, 7]],
+            groups("a\nb\nc\nx\nd\ne\nx", "x", 2, 0)
+        );
+    }
+
+    #[test]
+    fn stops_when_asked() {
+        let mut lines = 0;
+
+        search(&Fixed::new("x", false), "x\nx\nx".as_bytes(), 0, 0, |_| {
+            lines += 1;
+            Ok(lines < 2)
+        })
+        .unwrap();
+
+        assert_eq!(2, lines);
+    }
+
+    #[test]
+    fn not_utf8() {
+        let matcher = Fixed::new("x", false);
+
+        assert!(contains_match(&matcher, "a\nx\n".as_bytes()).unwrap());
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            contains_match(&matcher, &b"a\n\xff\n"[..])
+                .unwrap_err()
+                .kind()
+        );
+    }
+}
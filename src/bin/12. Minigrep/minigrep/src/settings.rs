@@ -0,0 +1,515 @@
+use crate::{Ambiguity, ColorChoice, Flags, Folding, MinigrepError};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::PathBuf;
+
+/// Where the value of a setting comes from. Each one overrides the ones above
+/// it.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub enum Source {
+    #[default]
+    Default,
+    /// The config file, see [`config_path`].
+    File(PathBuf),
+    /// An environment variable, like `MINIGREP_COLOR`.
+    Env(String),
+    Flag,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "{var}"),
+            Source::Flag => write!(f, "command line"),
+        }
+    }
+}
+
+/// A setting and where its value comes from.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+    /// Where the value came from before a flag replaced it, if not the
+    /// default.
+    pub overridden: Option<Source>,
+}
+
+/// The preferences that can be set outside of the command line, in the config
+/// file and in `MINIGREP_*` environment variables.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Settings {
+    pub ignore_case: Setting<bool>,
+    pub case_folding: Setting<Folding>,
+    pub color: Setting<ColorChoice>,
+    pub line_number: Setting<bool>,
+    pub column: Setting<bool>,
+    pub before_context: Setting<usize>,
+    pub after_context: Setting<usize>,
+    pub files: Setting<Ambiguity>,
+}
+
+/// The names of the settings, in the config file and in `--show-config`. The
+/// environment variables are the same in upper snake case after `MINIGREP_`.
+const KEYS: [&str; 8] = [
+    "ignore-case",
+    "case-folding",
+    "color",
+    "line-number",
+    "column",
+    "before-context",
+    "after-context",
+    "files",
+];
+
+/// The variable from the book, the same as `MINIGREP_IGNORE_CASE` but weaker.
+pub const IGNORE_CASE_VAR: &str = "IGNORE_CASE";
+
+/// The variable with another path for the config file.
+pub const CONFIG_VAR: &str = "MINIGREP_CONFIG";
+
+/// The config file: `$MINIGREP_CONFIG`, `$XDG_CONFIG_HOME/minigrep/config.toml`
+/// or `~/.config/minigrep/config.toml`.
+pub fn config_path(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if let Some(path) = env(CONFIG_VAR) {
+        return Some(PathBuf::from(path));
+    }
+
+    let config = match env("XDG_CONFIG_HOME") {
+        Some(config) => PathBuf::from(config),
+        None => PathBuf::from(env("HOME")?).join(".config"),
+    };
+    Some(config.join("minigrep").join("config.toml"))
+}
+
+impl Settings {
+    /// The defaults with `flags` on top, without reading anything.
+    pub fn from_flags(flags: &[Flags]) -> Settings {
+        let mut settings = Settings::default();
+        settings.apply_flags(flags);
+        settings
+    }
+
+    /// The defaults, then the config file, then the environment variables and
+    /// then `flags`. `env` reads a variable, empty ones count as unset.
+    pub fn load(
+        flags: &[Flags],
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Settings, MinigrepError> {
+        let env = |var: &str| env(var).filter(|value| !value.is_empty());
+        let mut settings = Settings::default();
+
+        if let Some(path) = config_path(env) {
+            match fs::read_to_string(&path) {
+                Ok(contents) => settings.apply_file(path, &contents)?,
+                // Having no config file is fine
+                Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => return Err(MinigrepError::io(path, e)),
+            }
+        }
+
+        if let Some(value) = env(IGNORE_CASE_VAR) {
+            let source = Source::Env(IGNORE_CASE_VAR.to_string());
+            if !settings.ignore_case.set_str(&value, source) {
+                return Err(MinigrepError::Settings {
+                    origin: IGNORE_CASE_VAR.to_string(),
+                    message: format!("invalid value {value:?}"),
+                });
+            }
+        }
+        for key in KEYS {
+            let var = format!("MINIGREP_{}", key.to_uppercase().replace('-', "_"));
+            let Some(value) = env(&var) else {
+                continue;
+            };
+            if !settings
+                .entry(key)
+                .set_str(&value, Source::Env(var.clone()))
+            {
+                return Err(MinigrepError::Settings {
+                    origin: var,
+                    message: format!("invalid value {value:?}"),
+                });
+            }
+        }
+
+        settings.apply_flags(flags);
+        Ok(settings)
+    }
+
+    fn apply_file(&mut self, path: PathBuf, contents: &str) -> Result<(), MinigrepError> {
+        let table: toml::Table = match contents.parse() {
+            Ok(table) => table,
+            Err(source) => return Err(MinigrepError::ConfigFile { path, source }),
+        };
+        let error = |message| MinigrepError::Settings {
+            origin: path.display().to_string(),
+            message,
+        };
+
+        for (key, value) in &table {
+            let Some(key) = KEYS.into_iter().find(|known| known == key) else {
+                return Err(error(format!("unknown setting {key}")));
+            };
+            if !self.entry(key).set_toml(value, Source::File(path.clone())) {
+                return Err(error(format!("invalid {key} {value}")));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like grep, `-A` and `-B` win over `-C` whatever their order, otherwise
+    /// the last flag wins.
+    fn apply_flags(&mut self, flags: &[Flags]) {
+        let (context, others): (Vec<&Flags>, Vec<&Flags>) = flags
+            .iter()
+            .partition(|flag| matches!(flag, Flags::Context(_)));
+
+        for flag in context.into_iter().chain(others) {
+            match *flag {
+                Flags::IgnoreCase => self.ignore_case.set_flag(true),
+                Flags::NoIgnoreCase => self.ignore_case.set_flag(false),
+                Flags::CaseFolding(folding) => self.case_folding.set_flag(folding),
+                Flags::Color(choice) => self.color.set_flag(choice),
+                Flags::LineNumber => self.line_number.set_flag(true),
+                Flags::NoLineNumber => self.line_number.set_flag(false),
+                Flags::Column => self.column.set_flag(true),
+                Flags::NoColumn => self.column.set_flag(false),
+                Flags::Context(lines) => {
+                    self.before_context.set_flag(lines);
+                    self.after_context.set_flag(lines);
+                }
+                Flags::BeforeContext(lines) => self.before_context.set_flag(lines),
+                Flags::AfterContext(lines) => self.after_context.set_flag(lines),
+                Flags::FirstFile => self.files.set_flag(Ambiguity::First),
+                Flags::AllFiles => self.files.set_flag(Ambiguity::All),
+                Flags::UniqueFile => self.files.set_flag(Ambiguity::Error),
+                _ => (),
+            }
+        }
+    }
+
+    fn entry(&mut self, key: &str) -> &mut dyn Entry {
+        match key {
+            "ignore-case" => &mut self.ignore_case,
+            "case-folding" => &mut self.case_folding,
+            "color" => &mut self.color,
+            "line-number" => &mut self.line_number,
+            "column" => &mut self.column,
+            "before-context" => &mut self.before_context,
+            "after-context" => &mut self.after_context,
+            "files" => &mut self.files,
+            _ => unreachable!("{key} is not in KEYS"),
+        }
+    }
+}
+
+/// The settings as a config file, with where each one comes from, for
+/// `--show-config`.
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut settings = self.clone();
+        let width = KEYS.iter().map(|key| key.len()).max().unwrap_or(0);
+
+        for key in KEYS {
+            let (value, source) = settings.entry(key).show();
+            writeln!(f, "{key:<width$} = {value:<8} # {source}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Setting<T> {
+    fn set_flag(&mut self, value: T) {
+        self.value = value;
+        match mem::replace(&mut self.source, Source::Flag) {
+            Source::Default | Source::Flag => (),
+            source => self.overridden = Some(source),
+        }
+    }
+
+    fn replace(&mut self, value: Option<T>, source: Source) -> bool {
+        let Some(value) = value else {
+            return false;
+        };
+        self.value = value;
+        self.source = source;
+        true
+    }
+}
+
+/// A [`Setting`] of any type.
+trait Entry {
+    /// Sets the value from an environment variable, returns whether it is
+    /// valid.
+    fn set_str(&mut self, value: &str, source: Source) -> bool;
+    /// Sets the value from the config file, returns whether it is valid.
+    fn set_toml(&mut self, value: &toml::Value, source: Source) -> bool;
+    /// The value as written in the config file, and its source.
+    fn show(&self) -> (String, String);
+}
+
+impl<T: Value> Entry for Setting<T> {
+    fn set_str(&mut self, value: &str, source: Source) -> bool {
+        self.replace(T::from_str(value), source)
+    }
+
+    fn set_toml(&mut self, value: &toml::Value, source: Source) -> bool {
+        self.replace(T::from_toml(value), source)
+    }
+
+    fn show(&self) -> (String, String) {
+        let source = match &self.overridden {
+            Some(overridden) => format!("{}, over {overridden}", self.source),
+            None => self.source.to_string(),
+        };
+        (self.value.to_toml(), source)
+    }
+}
+
+/// A type of setting, written the same way in the config file and in
+/// `--show-config`.
+trait Value: Sized {
+    fn from_str(value: &str) -> Option<Self>;
+
+    fn from_toml(value: &toml::Value) -> Option<Self> {
+        value.as_str().and_then(Self::from_str)
+    }
+
+    fn to_toml(&self) -> String;
+}
+
+impl Value for bool {
+    fn from_str(value: &str) -> Option<bool> {
+        match value {
+            "1" | "true" | "yes" | "on" => Some(true),
+            "0" | "false" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn from_toml(value: &toml::Value) -> Option<bool> {
+        value.as_bool()
+    }
+
+    fn to_toml(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Value for usize {
+    fn from_str(value: &str) -> Option<usize> {
+        value.parse().ok()
+    }
+
+    fn from_toml(value: &toml::Value) -> Option<usize> {
+        value.as_integer()?.try_into().ok()
+    }
+
+    fn to_toml(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Value for Folding {
+    fn from_str(value: &str) -> Option<Folding> {
+        Folding::parse(value)
+    }
+
+    fn to_toml(&self) -> String {
+        match self {
+            Folding::Simple => String::from("\"simple\""),
+            Folding::Full => String::from("\"full\""),
+        }
+    }
+}
+
+impl Value for ColorChoice {
+    fn from_str(value: &str) -> Option<ColorChoice> {
+        ColorChoice::parse(value)
+    }
+
+    fn to_toml(&self) -> String {
+        match self {
+            ColorChoice::Auto => String::from("\"auto\""),
+            ColorChoice::Always => String::from("\"always\""),
+            ColorChoice::Never => String::from("\"never\""),
+        }
+    }
+}
+
+/// Named after `--first-file`, `--all-files` and `--unique-file`.
+impl Value for Ambiguity {
+    fn from_str(value: &str) -> Option<Ambiguity> {
+        match value {
+            "first" => Some(Ambiguity::First),
+            "all" => Some(Ambiguity::All),
+            "unique" => Some(Ambiguity::Error),
+            _ => None,
+        }
+    }
+
+    fn to_toml(&self) -> String {
+        match self {
+            Ambiguity::First => String::from("\"first\""),
+            Ambiguity::All => String::from("\"all\""),
+            Ambiguity::Error => String::from("\"unique\""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::env;
+
+    fn config_file(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(format!("minigrep-{name}-{}.toml", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    fn load(flags: &[Flags], vars: &[(&str, &str)]) -> Result<Settings, MinigrepError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(var, value)| (var.to_string(), value.to_string()))
+            .collect();
+        Settings::load(flags, |var| vars.get(var).cloned())
+    }
+
+    #[test]
+    fn paths() {
+        let env = |vars: &'static [(&str, &str)]| {
+            move |var: &str| {
+                vars.iter()
+                    .find(|(v, _)| *v == var)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            Some(PathBuf::from("/home/ferris/.config/minigrep/config.toml")),
+            config_path(env(&[("HOME", "/home/ferris")]))
+        );
+        assert_eq!(
+            Some(PathBuf::from("/xdg/minigrep/config.toml")),
+            config_path(env(&[
+                ("HOME", "/home/ferris"),
+                ("XDG_CONFIG_HOME", "/xdg")
+            ]))
+        );
+        assert_eq!(None, config_path(env(&[])));
+    }
+
+    #[test]
+    fn layers() {
+        let path = config_file(
+            "layers",
+            "color = \"always\"\nline-number = true\nafter-context = 2\nfiles = \"first\"\n",
+        );
+        let vars = [
+            (CONFIG_VAR, path.as_str()),
+            ("IGNORE_CASE", "1"),
+            ("MINIGREP_LINE_NUMBER", "false"),
+            ("MINIGREP_COLUMN", ""),
+            ("MINIGREP_CASE_FOLDING", "simple"),
+        ];
+        let settings = load(&[Flags::Context(3), Flags::FirstFile], &vars).unwrap();
+
+        let file = Source::File(PathBuf::from(&path));
+        let env = |var: &str| Source::Env(var.to_string());
+
+        assert_eq!(
+            (true, env("IGNORE_CASE")),
+            (settings.ignore_case.value, settings.ignore_case.source)
+        );
+        assert_eq!(
+            (ColorChoice::Always, file.clone()),
+            (settings.color.value, settings.color.source)
+        );
+        assert_eq!(
+            (false, env("MINIGREP_LINE_NUMBER")),
+            (settings.line_number.value, settings.line_number.source)
+        );
+        assert_eq!(
+            (false, Source::Default),
+            (settings.column.value, settings.column.source)
+        );
+        assert_eq!(Folding::Simple, settings.case_folding.value);
+        assert_eq!(
+            (3, Source::Flag),
+            (settings.after_context.value, settings.after_context.source)
+        );
+        assert_eq!(
+            Setting {
+                value: Ambiguity::First,
+                source: Source::Flag,
+                overridden: Some(file.clone()),
+            },
+            settings.files
+        );
+        fs::remove_file(&path).unwrap();
+
+        // The more specific variable wins over the one from the book
+        let vars = [
+            (CONFIG_VAR, "/missing.toml"),
+            ("IGNORE_CASE", "1"),
+            ("MINIGREP_IGNORE_CASE", "0"),
+        ];
+        assert!(!load(&[], &vars).unwrap().ignore_case.value);
+        // Flags win over everything
+        assert!(load(&[Flags::IgnoreCase], &vars).unwrap().ignore_case.value);
+        let vars = [(CONFIG_VAR, "/missing.toml"), ("IGNORE_CASE", "1")];
+        assert!(
+            !load(&[Flags::NoIgnoreCase], &vars)
+                .unwrap()
+                .ignore_case
+                .value
+        );
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let path = config_file("unknown", "colour = \"always\"");
+        let e = load(&[], &[(CONFIG_VAR, &path)]).unwrap_err();
+        assert_eq!(format!("{path}: unknown setting colour"), e.to_string());
+        fs::remove_file(&path).unwrap();
+
+        let path = config_file("invalid", "after-context = -1");
+        let e = load(&[], &[(CONFIG_VAR, &path)]).unwrap_err();
+        assert_eq!(format!("{path}: invalid after-context -1"), e.to_string());
+        fs::remove_file(&path).unwrap();
+
+        let vars = [
+            (CONFIG_VAR, "/missing.toml"),
+            ("MINIGREP_COLOR", "sometimes"),
+        ];
+        let e = load(&[], &vars).unwrap_err();
+        assert_eq!("MINIGREP_COLOR: invalid value \"sometimes\"", e.to_string());
+    }
+
+    #[test]
+    fn show() {
+        let vars = [
+            (CONFIG_VAR, "/missing.toml"),
+            ("MINIGREP_FILES", "unique"),
+            ("IGNORE_CASE", "1"),
+        ];
+        let flags = [Flags::Color(ColorChoice::Never), Flags::NoIgnoreCase];
+        let settings = load(&flags, &vars).unwrap();
+        let shown = settings.to_string();
+
+        assert!(shown.contains("ignore-case    = false    # command line, over IGNORE_CASE\n"));
+        assert!(shown.contains("color          = \"never\"  # command line\n"));
+        assert!(shown.contains("files          = \"unique\" # MINIGREP_FILES\n"));
+        // It is a valid config file
+        assert!(shown.parse::<toml::Table>().is_ok());
+    }
+}
@@ -1,39 +1,11 @@
-use std::{thread, time::Duration, vec};
+#![allow(unused)]
 
-// Capturing the Environtment with Closures
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum ShirtColor {
-    Red,
-    Blue,
-}
-
-struct Inventory {
-    shirts: Vec<ShirtColor>,
-}
+use std::{thread, time::Duration, vec};
 
-impl Inventory {
-    fn giveaway(&self, user_preference: Option<ShirtColor>) -> ShirtColor {
-        user_preference.unwrap_or_else(|| self.most_stocked())
-    }
+use learning_rust::iterators_and_closures::{Inventory, ShirtColor};
 
-    fn most_stocked(&self) -> ShirtColor {
-        let mut num_red = 0;
-        let mut num_blue = 0;
-
-        for color in &self.shirts {
-            match color {
-                ShirtColor::Red => num_red += 1,
-                ShirtColor::Blue => num_blue += 1,
-            }
-        }
-
-        if num_red > num_blue {
-            ShirtColor::Red
-        } else {
-            ShirtColor::Blue
-        }
-    }
-}
+// Capturing the Environtment with Closures
+// Inventory::giveaway captures self in a closure, see src/iterators_and_closures.rs
 
 fn main() {
     let store = Inventory {
@@ -69,7 +41,7 @@ fn main() {
 
     let example_closure = |x| x;
     let s = example_closure(String::from("hello"));
-    // let n = example_closure(5); // error: mismatched types expected `String`, found integer rustc(E0308)
+    // let n = example_closure(5); // error[E0308]: mismatched types: expected `String`, found integer
 
     // Capturing References or Moving Ownership
     // immutable borrow
@@ -134,8 +106,9 @@ fn main() {
     println!("{:#?}, sorted in {num_sort_operations} operations", list);
 
     // Closures Must Name Captured Lifetimes
+    // error[E0700]
     // fn make_a_cloner(s_ref: &str) -> impl Fn() -> String {
-    //     move || s_ref.to_owned() // error! borrowed data cannot be stored outside of its closure
+    //     move || s_ref.to_owned() // borrowed data cannot be stored outside of its closure, error: hidden type for `impl Fn() -> String` captures lifetime
     // }
 
     fn make_a_cloner(s_ref: &str) -> impl Fn() -> String + '_ {
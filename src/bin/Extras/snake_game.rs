@@ -1,3 +1,7 @@
+//! stdin = true
+
+#![allow(clippy::collapsible_match)]
+
 use rand::Rng;
 use std::io::{self, Write};
 use std::thread;
@@ -64,7 +68,7 @@ fn main() {
     let mut snake = Snake::new(WIDTH / 2, HEIGHT / 2);
     let mut apple_x;
     let mut apple_y;
-    let mut rng = rand::thread_rng();
+    let mut rng = learning_rust::rng();
 
     loop {
         apple_x = rng.gen_range(0..WIDTH);
@@ -0,0 +1,239 @@
+//! Lists, searches and runs the lessons using the generated `chapters.toml`.
+
+use bin_manifest::{Chapter, Index, Kind, Section};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+const USAGE: &str = "\
+Usage: learn [command]
+
+Commands:
+  list              Lists every chapter and section (default)
+  search <words>    Fuzzy finds sections by their chapter and section title
+  run <id>          Runs a section by its id (4.3) or target name
+  <id>              Same as run <id>
+  next              Runs the first section that was not run yet
+  reset             Forgets which sections were run
+
+Options:
+  -h, --help        Prints help information";
+
+const PROGRESS_FILE: &str = ".learn-progress";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let index = fs::read_to_string(root.join("chapters.toml"))
+        .map_err(|e| e.to_string())
+        .and_then(|index| index.parse::<Index>().map_err(|e| e.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to read chapters.toml, run `cargo build` first: {err}");
+            process::exit(2);
+        });
+    let mut progress = Progress::load(root.join(PROGRESS_FILE));
+
+    match args.first().map(String::as_str) {
+        None | Some("list") => list(&index, &progress),
+        Some("-h") | Some("--help") => println!("{USAGE}"),
+        Some("search") => search(&index, &args[1..].join(" ")),
+        Some("reset") => progress.reset(),
+        Some("next") => match index
+            .sections()
+            .find(|(_, section)| !progress.is_read(section))
+        {
+            Some((chapter, section)) => run(root, chapter, section, &mut progress),
+            None => println!("🎉 You have run every section!"),
+        },
+        Some("run") if args.len() < 2 => {
+            eprintln!("Missing section id\n\n{USAGE}");
+            process::exit(2);
+        }
+        Some(command) => {
+            let query = if command == "run" { &args[1] } else { command };
+
+            match index.find(query) {
+                Some((chapter, section)) => run(root, chapter, section, &mut progress),
+                None => {
+                    eprintln!("No section `{query}`, try `learn search {query}`");
+                    process::exit(2);
+                }
+            }
+        }
+    }
+}
+
+fn list(index: &Index, progress: &Progress) {
+    for chapter in &index.chapters {
+        match chapter.number {
+            Some(number) => println!("{number}. {}", chapter.title),
+            None => println!("{}", chapter.title),
+        }
+
+        for section in &chapter.sections {
+            print_section(chapter, section, progress);
+        }
+    }
+}
+
+fn search(index: &Index, query: &str) {
+    let mut results = index
+        .sections()
+        .filter_map(|(chapter, section)| {
+            let text = format!("{} {}", chapter.title, section.title);
+            fuzzy_score(query, &text).map(|score| (score, chapter, section))
+        })
+        .collect::<Vec<_>>();
+
+    // Best matches first, book order for equal scores
+    results.sort_by_key(|&(score, ..)| Reverse(score));
+
+    if results.is_empty() {
+        println!("No sections match \"{query}\"");
+    }
+
+    for (_, chapter, section) in results {
+        println!("{} > {}", chapter.title, section.title);
+        println!("    {}", id_or_name(chapter, section));
+    }
+}
+
+fn print_section(chapter: &Chapter, section: &Section, progress: &Progress) {
+    let mark = if progress.is_read(section) { "✔" } else { " " };
+    let stdin = if section.stdin { " ⌨" } else { "" };
+
+    println!(
+        "  {mark} {:<6} {}{stdin}",
+        section.id(chapter).unwrap_or_default(),
+        section.title
+    );
+}
+
+fn run(root: &Path, chapter: &Chapter, section: &Section, progress: &mut Progress) {
+    println!("▶ {} > {}", chapter.title, section.title);
+    if section.stdin {
+        println!("  This lesson reads from stdin, type your input below.");
+    }
+
+    let (command, flag) = match section.kind {
+        Kind::Bin => ("run", "--bin"),
+        Kind::Test => ("test", "--test"),
+        Kind::Example => ("run", "--example"),
+    };
+
+    // stdin, stdout and stderr are inherited, so interactive lessons just work
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let status = Command::new(cargo)
+        .args([command, "-q", flag, &section.name])
+        .current_dir(root)
+        .status()
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to run cargo: {e}");
+            process::exit(2);
+        });
+
+    progress.mark_read(section);
+
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+}
+
+fn id_or_name(chapter: &Chapter, section: &Section) -> String {
+    match section.id(chapter) {
+        Some(id) => format!("learn {id}"),
+        None => format!("learn {}", section.name),
+    }
+}
+
+/// Scores `query` as a case-insensitive subsequence of `text`, higher is
+/// better. Consecutive characters and word starts count extra.
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = text[position..].iter().position(|t| *t == c)? + position;
+
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 2;
+        }
+        if found == 0 || text[found - 1] == ' ' {
+            score += 3;
+        }
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+/// The target names of the sections that were already run, one per line.
+struct Progress {
+    path: PathBuf,
+    read: HashSet<String>,
+}
+
+impl Progress {
+    fn load(path: PathBuf) -> Progress {
+        let read = fs::read_to_string(&path)
+            .unwrap_or_default()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        Progress { path, read }
+    }
+
+    fn is_read(&self, section: &Section) -> bool {
+        self.read.contains(&section.name)
+    }
+
+    fn mark_read(&mut self, section: &Section) {
+        if self.read.insert(section.name.clone()) {
+            self.save();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.read.clear();
+        self.save();
+    }
+
+    fn save(&self) {
+        let mut names = self.read.iter().cloned().collect::<Vec<_>>();
+        names.sort();
+
+        if let Err(e) = fs::write(&self.path, names.join("\n")) {
+            eprintln!("Failed to save progress to {}: {e}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_matches_subsequences() {
+        assert!(fuzzy_score("ownrshp", "Ownership Slice Type").is_some());
+        assert!(fuzzy_score("slice", "Ownership Slice Type").is_some());
+        assert!(fuzzy_score("traits", "Ownership Slice Type").is_none());
+    }
+
+    #[test]
+    fn fuzzy_prefers_word_starts() {
+        let word = fuzzy_score("slice", "Ownership Slice Type").unwrap();
+        let scattered = fuzzy_score("slice", "Structs Example Defining Structs").unwrap_or(0);
+
+        assert!(word > scattered);
+    }
+}
@@ -0,0 +1,33 @@
+//! 9. Error Handling
+
+/// A number between 1 and 100, from `09. Error Handling/3_whether_to_panic.rs`.
+///
+/// The range is checked once in [`Guess::new`] and [`Guess::set`], so code
+/// that gets a `Guess` does not have to check it again.
+pub struct Guess {
+    value: i32,
+}
+
+impl Guess {
+    /// Panics if `value` is not between 1 and 100.
+    pub fn new(value: i32) -> Guess {
+        Guess::validate_value(value);
+        Guess { value }
+    }
+
+    pub fn get(&self) -> i32 {
+        self.value
+    }
+
+    /// Panics if `value` is not between 1 and 100.
+    pub fn set(&mut self, value: i32) {
+        Guess::validate_value(value);
+        self.value = value;
+    }
+
+    fn validate_value(value: i32) {
+        if !(1..=100).contains(&value) {
+            panic!("Guess value must be between 1 and 100, got {}.", value);
+        }
+    }
+}
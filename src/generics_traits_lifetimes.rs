@@ -0,0 +1,68 @@
+//! 10. Generics, Traits, Lifetimes
+
+// Generic Data Types
+
+/// A point whose coordinates have the same type.
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub fn x(&self) -> &T {
+        &self.x
+    }
+}
+
+// Only points of f32 have this method
+impl Point<f32> {
+    pub fn distance_from_origin(&self) -> f32 {
+        (self.x.powi(2) + self.y.powi(2)).sqrt()
+    }
+}
+
+// Traits
+
+pub trait Summary {
+    fn summarize(&self) -> String;
+}
+
+/// Gets its `Summary` from a default implementation in `2_traits.rs`.
+pub struct NewsArticle {
+    pub headline: String,
+    pub location: String,
+    pub author: String,
+    pub content: String,
+}
+
+pub struct Tweet {
+    pub username: String,
+    pub content: String,
+    pub reply: bool,
+    pub retweet: bool,
+}
+
+impl Summary for Tweet {
+    fn summarize(&self) -> String {
+        format!("{}: {}", self.username, self.content)
+    }
+}
+
+// Lifetimes
+
+/// Holds a part of a text it does not own, so it cannot outlive the text.
+pub struct ImportantExcerpt<'a> {
+    pub part: &'a str,
+}
+
+impl<'a> ImportantExcerpt<'a> {
+    pub fn level(&self) -> i32 {
+        3
+    }
+
+    // The returned reference gets the lifetime of &self by the elision rules
+    pub fn announce_and_return_part(&self, announcement: &str) -> &str {
+        println!("Attention please: {}", announcement);
+        self.part
+    }
+}
@@ -0,0 +1,38 @@
+//! 13. Iterators and Closures
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ShirtColor {
+    Red,
+    Blue,
+}
+
+/// The shirt company's stock, from `13. Iterators and Closures/1_closures.rs`.
+pub struct Inventory {
+    pub shirts: Vec<ShirtColor>,
+}
+
+impl Inventory {
+    /// The preferred color, or the one with the most shirts in stock.
+    pub fn giveaway(&self, user_preference: Option<ShirtColor>) -> ShirtColor {
+        // The closure captures an immutable reference to self
+        user_preference.unwrap_or_else(|| self.most_stocked())
+    }
+
+    pub fn most_stocked(&self) -> ShirtColor {
+        let mut num_red = 0;
+        let mut num_blue = 0;
+
+        for color in &self.shirts {
+            match color {
+                ShirtColor::Red => num_red += 1,
+                ShirtColor::Blue => num_blue += 1,
+            }
+        }
+
+        if num_red > num_blue {
+            ShirtColor::Red
+        } else {
+            ShirtColor::Blue
+        }
+    }
+}
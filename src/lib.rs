@@ -0,0 +1,24 @@
+//! The types the lessons build up, so the bins and the tests in `tests/` can
+//! share them. One module per chapter of the book.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::env;
+
+pub mod error_handling;
+pub mod generics_traits_lifetimes;
+pub mod iterators_and_closures;
+pub mod structs;
+
+/// Seeds [`rng`] when set, so the lessons that roll dice print the same
+/// thing on every run.
+pub const SEED_VAR: &str = "LEARNING_RUST_SEED";
+
+/// The random number generator for the lessons, seeded from [`SEED_VAR`] or
+/// from the OS when it is not set.
+pub fn rng() -> StdRng {
+    match env::var(SEED_VAR).ok().and_then(|seed| seed.parse().ok()) {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
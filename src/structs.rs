@@ -0,0 +1,43 @@
+//! 5. Structs
+
+/// The rectangle from `05. Structs/3_method_syntax.rs`.
+#[derive(Debug)]
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rectangle {
+    pub fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    // A method can have the same name as a field
+    pub fn width(&self) -> bool {
+        self.width > 0
+    }
+
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width;
+    }
+
+    // Takes ownership of both rectangles
+    pub fn max(self, other: Self) -> Self {
+        Self {
+            width: self.width.max(other.width),
+            height: self.height.max(other.height),
+        }
+    }
+
+    pub fn can_hold(&self, other: &Self) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    // Associated function, called as Rectangle::square(3)
+    pub fn square(size: u32) -> Self {
+        Self {
+            width: size,
+            height: size,
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Checks that the commented-out snippets tagged with `error!` still fail to
+//! compile, and fail with the error the lesson says they do.
+//!
+//! A tag at the end of a commented line marks just that line:
+//!
+//! ```text
+//! // s.clear(); // error: cannot borrow `s` as mutable because it is also borrowed as immutable
+//! ```
+//!
+//! A tag on its own line marks the commented lines below it, up to the next
+//! blank or uncommented line. Tags at the end of those lines expect one more
+//! error from the whole block:
+//!
+//! ```text
+//! // error[E0106]
+//! // fn longest(x: &str, y: &str) -> &str {
+//! //     x // error: missing lifetime specifier
+//! // }
+//! ```
+//!
+//! A tag on its own line with no commented lines below it cannot be checked.
+//! These notes are listed in [`NOTES`], so that new ones are not missed.
+//!
+//! A bare `error!` expects any error. Text after `error!` or `error:` expects
+//! an error that starts with it, as printed by `rustc --error-format=short`,
+//! and an error code like `[E0106]` expects that code too, with or without
+//! text. Each snippet is uncommented on its own and the lesson is compiled
+//! with the local rustc.
+
+use bin_manifest::{Generator, Kind, Target};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Snippet {
+    /// 1-based line of the tag.
+    line: usize,
+    /// 0-based lines to uncomment, none for a tag with no commented lines
+    /// below it, which is only a note.
+    lines: Range<usize>,
+    expected: String,
+}
+
+/// An error reported by rustc, the message is followed by the label of its
+/// primary span: `borrow of moved value: `first`: value borrowed here after move`.
+#[derive(Debug)]
+struct Diagnostic {
+    code: Option<String>,
+    message: String,
+}
+
+/// The tags with no snippet to check, by lesson and expected error.
+const NOTES: &[(&str, &str)] = &[(
+    "src/bin/10. Generics, Traits, Lifetimes/2_traits.rs",
+    "`if` and `else` have incompatible types",
+)];
+
+#[test]
+fn snippets_fail_with_the_annotated_error() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let discovery = Generator::new()
+        .root(root)
+        .discover()
+        .expect("Failed to discover the lessons");
+
+    let failures = thread::scope(|scope| {
+        let handles = discovery
+            .targets
+            .iter()
+            .map(|target| scope.spawn(|| check(root, target)))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+
+    assert!(
+        failures.is_empty(),
+        "{} stale error annotations:\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
+
+#[test]
+fn notes_are_listed() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let discovery = Generator::new()
+        .root(root)
+        .discover()
+        .expect("Failed to discover the lessons");
+
+    let notes = discovery
+        .targets
+        .iter()
+        .flat_map(|target| {
+            let source = fs::read_to_string(root.join(&target.path)).unwrap();
+            snippets(&source)
+                .into_iter()
+                .filter(|snippet| snippet.lines.is_empty())
+                .map(|snippet| (target.path.clone(), snippet.expected))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let notes = notes
+        .iter()
+        .map(|(path, expected)| (path.as_str(), expected.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        NOTES, notes,
+        "tags with no commented lines below them are not checked"
+    );
+}
+
+/// Compiles every snippet of a lesson, returns one report per stale snippet.
+fn check(root: &Path, target: &Target) -> Vec<String> {
+    let source = fs::read_to_string(root.join(&target.path)).unwrap();
+    let snippets = snippets(&source);
+    if snippets.is_empty() {
+        return Vec::new();
+    }
+
+    // Otherwise the errors could come from somewhere else
+    let errors = compile(target, &source);
+    if !errors.is_empty() {
+        return vec![format!(
+            "{}: does not compile without the snippets:\n{}",
+            target.path,
+            list(&errors)
+        )];
+    }
+
+    snippets
+        .iter()
+        .filter(|snippet| !snippet.lines.is_empty())
+        .filter_map(|snippet| {
+            let errors = compile(target, &uncomment(&source, &snippet.lines));
+            let location = format!("{}:{}", target.path, snippet.line);
+
+            if errors.is_empty() {
+                Some(format!("{location}: compiles, expected an error"))
+            } else if !errors.iter().any(|error| matches(&snippet.expected, error)) {
+                Some(format!(
+                    "{location}: expected `{}`, rustc reported:\n{}",
+                    snippet.expected,
+                    list(&errors)
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Finds the tagged snippets in a lesson.
+fn snippets(source: &str) -> Vec<Snippet> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut snippets = Vec::new();
+    let mut block = 0..0;
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(text) = comment(line) else {
+            continue;
+        };
+
+        let own_line = text.trim_start().starts_with("error");
+        if let Some(expected) = tag(text).filter(|_| own_line && !block.contains(&i)) {
+            let end = (i + 1..lines.len())
+                .find(|&j| comment(lines[j]).is_none_or(|text| text.trim().is_empty()))
+                .unwrap_or(lines.len());
+
+            block = i + 1..end;
+            snippets.push(Snippet {
+                line: i + 1,
+                lines: block.clone(),
+                expected,
+            });
+        } else if let Some(expected) = text.split_once("//").and_then(|(_, note)| tag(note)) {
+            // A tag inside a block expects one of the errors of the whole block
+            let lines = match block.contains(&i) {
+                true => block.clone(),
+                false => i..i + 1,
+            };
+            snippets.push(Snippet {
+                line: i + 1,
+                lines,
+                expected,
+            });
+        }
+    }
+
+    snippets
+}
+
+/// The text after `//`, `None` for code and doc comments.
+fn comment(line: &str) -> Option<&str> {
+    let text = line.trim_start().strip_prefix("//")?;
+
+    if text.starts_with('/') || text.starts_with('!') {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// The expectation after an `error!` or `error:` tag, which may follow other
+/// notes like `- p: R🟧 error!`.
+fn tag(note: &str) -> Option<String> {
+    let note = note.trim();
+    let start = note
+        .match_indices("error")
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || note[..i].ends_with(' '))?;
+
+    let rest = &note[start + "error".len()..];
+    let expected = match rest.chars().next()? {
+        '!' | ':' => &rest[1..],
+        '[' => rest,
+        _ => return None,
+    };
+
+    Some(expected.trim().to_string())
+}
+
+fn uncomment(source: &str, lines: &Range<usize>) -> String {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if !lines.contains(&i) {
+                return line.to_string();
+            }
+
+            let indent = line.len() - line.trim_start().len();
+            let code = &line[indent + "//".len()..];
+            format!(
+                "{}{}",
+                &line[..indent],
+                code.strip_prefix(' ').unwrap_or(code)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn matches(expected: &str, error: &Diagnostic) -> bool {
+    let (code, text) = match expected
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    {
+        Some((code, text)) => (Some(code), text.trim_start_matches(':').trim()),
+        None => (None, expected),
+    };
+
+    code.is_none_or(|code| error.code.as_deref() == Some(code)) && error.message.starts_with(text)
+}
+
+fn list(errors: &[Diagnostic]) -> String {
+    errors
+        .iter()
+        .map(|error| match &error.code {
+            Some(code) => format!("    error[{code}]: {}", error.message),
+            None => format!("    error: {}", error.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Type and borrow checks `source` as `target`, returns the errors.
+fn compile(target: &Target, source: &str) -> Vec<Diagnostic> {
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let out = env::temp_dir().join(format!(
+        "compile-fail-{}-{}.rmeta",
+        std::process::id(),
+        target.name
+    ));
+
+    let mut rustc = Command::new(env::var("RUSTC").unwrap_or_else(|_| String::from("rustc")));
+    rustc
+        .args([
+            "-",
+            "--edition",
+            "2021",
+            "--emit=metadata",
+            "--error-format=short",
+        ])
+        .args(["--crate-name", &target.name, "-A", "warnings", "-o"])
+        .arg(&out)
+        .arg("-L")
+        .arg(format!("dependency={}", deps.display()));
+    match target.kind {
+        Kind::Test => rustc.arg("--test"),
+        _ => rustc.args(["--crate-type", "bin"]),
+    };
+    for (name, path) in externs(&deps) {
+        rustc
+            .arg("--extern")
+            .arg(format!("{name}={}", path.display()));
+    }
+
+    let mut child = rustc
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run rustc");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(source.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let _ = fs::remove_file(&out);
+
+    // `<anon>:53:20: error[E0382]: borrow of moved value: `first``
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter_map(|line| line.split_once(": error").map(|(_, error)| error))
+        .map(
+            |error| match error.strip_prefix('[').and_then(|e| e.split_once("]: ")) {
+                Some((code, message)) => Diagnostic {
+                    code: Some(code.to_string()),
+                    message: message.to_string(),
+                },
+                None => Diagnostic {
+                    code: None,
+                    message: error.trim_start_matches(": ").to_string(),
+                },
+            },
+        )
+        .collect()
+}
+
+/// The package's library and dependencies built for this test, the newest
+/// build of each.
+fn externs(deps: &Path) -> Vec<(String, PathBuf)> {
+    let manifest = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .unwrap()
+        .parse::<toml::Table>()
+        .unwrap();
+    let entries = fs::read_dir(deps)
+        .unwrap()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    manifest["dependencies"]
+        .as_table()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .chain([env!("CARGO_PKG_NAME")])
+        .filter_map(|name| {
+            let name = name.replace('-', "_");
+            let prefix = format!("lib{name}-");
+
+            let path = entries
+                .iter()
+                .filter(|entry| {
+                    let file_name = entry.file_name();
+                    let file_name = file_name.to_string_lossy();
+                    file_name.starts_with(&prefix) && file_name.ends_with(".rlib")
+                })
+                .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())?
+                .path();
+
+            Some((name, path))
+        })
+        .collect()
+}
+
+#[test]
+fn finds_tagged_snippets() {
+    let source = "\
+fn main() {
+    // s.clear(); // error: cannot borrow `s` as mutable
+    // print_point(&p); // - p: R🟧 error!
+    // this is not an error!
+
+    // Tweet {}
+    // error! `if` and `else` have incompatible types
+
+    // error[E0106]
+    // fn longest(x: &str, y: &str) -> &str {
+    //     x // error: missing lifetime specifier
+    // }
+}
+";
+
+    assert_eq!(
+        vec![
+            Snippet {
+                line: 2,
+                lines: 1..2,
+                expected: "cannot borrow `s` as mutable".to_string(),
+            },
+            Snippet {
+                line: 3,
+                lines: 2..3,
+                expected: String::new(),
+            },
+            Snippet {
+                line: 7,
+                lines: 7..7,
+                expected: "`if` and `else` have incompatible types".to_string(),
+            },
+            Snippet {
+                line: 9,
+                lines: 9..12,
+                expected: "[E0106]".to_string(),
+            },
+            Snippet {
+                line: 11,
+                lines: 9..12,
+                expected: "missing lifetime specifier".to_string(),
+            },
+        ],
+        snippets(source)
+    );
+    assert_eq!(
+        "    fn longest(x: &str, y: &str) -> &str {\n        x // error: missing lifetime specifier\n    }",
+        uncomment(source, &(9..12))
+            .lines()
+            .skip(9)
+            .take(3)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
+#[test]
+fn matches_codes_and_text() {
+    let error = Diagnostic {
+        code: Some("E0277".to_string()),
+        message: "cannot add `Option<i8>` to `i8`: no implementation for `i8 + Option<i8>`"
+            .to_string(),
+    };
+
+    assert!(matches("", &error));
+    assert!(matches("[E0277]", &error));
+    assert!(matches("[E0277]: cannot add `Option<i8>`", &error));
+    assert!(matches("cannot add", &error));
+    assert!(!matches("[E0277]: mismatched types", &error));
+    assert!(!matches("[E0308]: cannot add", &error));
+}
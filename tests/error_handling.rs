@@ -0,0 +1,21 @@
+use learning_rust::error_handling::Guess;
+
+#[test]
+fn keeps_valid_guesses() {
+    let mut guess = Guess::new(12);
+    guess.set(100);
+
+    assert_eq!(100, guess.get());
+}
+
+#[test]
+#[should_panic(expected = "Guess value must be between 1 and 100, got 200.")]
+fn greater_than_100() {
+    Guess::new(200);
+}
+
+#[test]
+#[should_panic(expected = "Guess value must be between 1 and 100, got 0.")]
+fn set_checks_the_range() {
+    Guess::new(1).set(0);
+}
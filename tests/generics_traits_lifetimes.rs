@@ -0,0 +1,32 @@
+use learning_rust::generics_traits_lifetimes::{ImportantExcerpt, Point, Summary, Tweet};
+
+#[test]
+fn points() {
+    assert_eq!(&5, Point { x: 5, y: 10 }.x());
+    assert_eq!(5.0, Point { x: 3.0, y: 4.0 }.distance_from_origin());
+}
+
+#[test]
+fn summarizes_tweets() {
+    let tweet = Tweet {
+        username: String::from("horse_ebooks"),
+        content: String::from("of course, as you probably already know, people"),
+        reply: false,
+        retweet: false,
+    };
+
+    assert_eq!(
+        "horse_ebooks: of course, as you probably already know, people",
+        tweet.summarize()
+    );
+}
+
+#[test]
+fn excerpt_returns_its_part() {
+    let novel = String::from("Call me Ishmael. Some years ago...");
+    let excerpt = ImportantExcerpt {
+        part: novel.split('.').next().unwrap(),
+    };
+
+    assert_eq!("Call me Ishmael", excerpt.announce_and_return_part("Hi"));
+}
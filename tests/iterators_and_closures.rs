@@ -0,0 +1,11 @@
+use learning_rust::iterators_and_closures::{Inventory, ShirtColor};
+
+#[test]
+fn giveaway() {
+    let store = Inventory {
+        shirts: vec![ShirtColor::Blue, ShirtColor::Red, ShirtColor::Blue],
+    };
+
+    assert_eq!(ShirtColor::Red, store.giveaway(Some(ShirtColor::Red)));
+    assert_eq!(ShirtColor::Blue, store.giveaway(None));
+}
@@ -0,0 +1,271 @@
+//! Runs every `[[bin]]` lesson and compares its output with the snapshots in
+//! `tests/snapshots`:
+//!
+//! - `<name>.stdout` and `<name>.stderr`, a missing file means no output.
+//!   A failing exit code is recorded at the end of `<name>.stderr`.
+//! - `<name>.stdin` is piped to the lesson. Lessons with `//! stdin = true`
+//!   need one, the others get an empty stdin.
+//!
+//! Each lesson runs in an empty temporary directory with [`SEED_VAR`] set, so
+//! the random numbers are the same on every run. After an intended change,
+//! write the new snapshots with:
+//!
+//! ```sh
+//! BLESS=1 cargo test --test snapshots
+//! ```
+
+use bin_manifest::{Generator, Kind, Target};
+use learning_rust::SEED_VAR;
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SEED: &str = "0";
+/// The guessing game and the snake game wait for input forever once their
+/// fixture runs out.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a lesson printed, in the same form as the snapshot files.
+#[derive(Debug, PartialEq, Eq)]
+struct Output {
+    stdout: String,
+    stderr: String,
+}
+
+#[test]
+fn lessons_match_their_snapshots() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let snapshots = root.join("tests/snapshots");
+    let bless = env::var_os("BLESS").is_some();
+
+    let bins = Generator::new()
+        .root(root)
+        .discover()
+        .expect("Failed to discover the lessons")
+        .targets
+        .into_iter()
+        .filter(|target| target.kind == Kind::Bin)
+        .collect::<Vec<_>>();
+
+    let mut failures = thread::scope(|scope| {
+        let handles = bins
+            .iter()
+            .map(|target| scope.spawn(|| check(&snapshots, target, bless)))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap().err())
+            .collect::<Vec<_>>()
+    });
+
+    failures.extend(stale(&snapshots, &bins, bless));
+
+    assert!(
+        failures.is_empty(),
+        "{} lessons do not match their snapshots, run `BLESS=1 cargo test --test snapshots` \
+         if the changes are intended:\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
+
+fn check(snapshots: &Path, target: &Target, bless: bool) -> Result<(), String> {
+    let path = |extension| snapshots.join(format!("{}.{extension}", target.name));
+
+    let stdin = match fs::read_to_string(path("stdin")) {
+        Ok(stdin) => stdin,
+        Err(_) if target.stdin => {
+            return Err(format!(
+                "{}: reads from stdin but has no {}",
+                target.name,
+                path("stdin").display()
+            ))
+        }
+        Err(_) => String::new(),
+    };
+
+    let actual = run(target, &stdin)?;
+
+    if bless {
+        write(&path("stdout"), &actual.stdout);
+        write(&path("stderr"), &actual.stderr);
+        return Ok(());
+    }
+
+    let expected = Output {
+        stdout: fs::read_to_string(path("stdout")).unwrap_or_default(),
+        stderr: fs::read_to_string(path("stderr")).unwrap_or_default(),
+    };
+
+    let mut diffs = Vec::new();
+    if let Some(diff) = diff(&expected.stdout, &actual.stdout) {
+        diffs.push(format!("  stdout {diff}"));
+    }
+    if let Some(diff) = diff(&expected.stderr, &actual.stderr) {
+        diffs.push(format!("  stderr {diff}"));
+    }
+
+    if diffs.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} ({}):\n{}",
+            target.name,
+            target.path,
+            diffs.join("\n")
+        ))
+    }
+}
+
+/// Runs the built lesson with `stdin` and waits at most [`TIMEOUT`].
+fn run(target: &Target, stdin: &str) -> Result<Output, String> {
+    // Integration tests are in target/<profile>/deps, next to the bins' folder
+    let exe = env::current_exe().unwrap();
+    let exe = exe.parent().unwrap().parent().unwrap().join(&target.name);
+    let dir = env::temp_dir().join(format!("snapshots-{}-{}", std::process::id(), target.name));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut child = Command::new(&exe)
+        .current_dir(&dir)
+        .env(SEED_VAR, SEED)
+        .env_remove("RUST_BACKTRACE")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("{}: failed to run {}: {e}", target.name, exe.display()))?;
+
+    // Dropping stdin closes it, so the lesson sees the end of the input
+    let mut input = child.stdin.take().unwrap();
+    let stdin = stdin.to_string();
+    let writer = thread::spawn(move || input.write_all(stdin.as_bytes()));
+    let stdout = read(child.stdout.take().unwrap());
+    let stderr = read(child.stderr.take().unwrap());
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if start.elapsed() > TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            let _ = fs::remove_dir_all(&dir);
+            return Err(format!(
+                "{}: still running after {TIMEOUT:?}, does its stdin fixture end the program?",
+                target.name
+            ));
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    // The lesson may exit without reading all of its input
+    let _ = writer.join();
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut stderr = normalize(&stderr.join().unwrap());
+    if !status.success() {
+        match status.code() {
+            Some(code) => stderr.push_str(&format!("[exit code: {code}]\n")),
+            None => stderr.push_str(&format!("[{status}]\n")),
+        }
+    }
+
+    Ok(Output {
+        stdout: stdout.join().unwrap(),
+        stderr,
+    })
+}
+
+/// Drops the thread ids from panic messages, `thread 'main' (1234) panicked`
+/// changes on every run.
+fn normalize(stderr: &str) -> String {
+    stderr
+        .split_inclusive('\n')
+        .map(|line| {
+            let Some((thread, rest)) = line.split_once("' (") else {
+                return line.to_string();
+            };
+            match rest.split_once(") panicked") {
+                Some((id, rest))
+                    if thread.starts_with("thread '") && id.bytes().all(|b| b.is_ascii_digit()) =>
+                {
+                    format!("{thread}' panicked{rest}")
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn read(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        pipe.read_to_end(&mut bytes).unwrap();
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// The first line that differs, `None` when both are the same.
+fn diff(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(expected), Some(actual)) if expected == actual => line += 1,
+            (None, None) => return Some("differs in line endings".to_string()),
+            (expected, actual) => {
+                return Some(format!(
+                    "differs at line {line}:\n    expected: {}\n    actual:   {}",
+                    expected.map_or("<end>".to_string(), |line| format!("{line:?}")),
+                    actual.map_or("<end>".to_string(), |line| format!("{line:?}")),
+                ))
+            }
+        }
+    }
+}
+
+/// Writes a snapshot, or removes it when the lesson printed nothing.
+fn write(path: &Path, contents: &str) {
+    if contents.is_empty() {
+        let _ = fs::remove_file(path);
+    } else {
+        fs::write(path, contents).unwrap();
+    }
+}
+
+/// Snapshots of lessons that no longer exist, removed when blessing.
+fn stale(snapshots: &Path, bins: &[Target], bless: bool) -> Vec<String> {
+    let files = fs::read_dir(snapshots)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+
+    files
+        .into_iter()
+        .filter(|path| {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            !bins.iter().any(|target| target.name == stem)
+        })
+        .filter_map(|path| {
+            if bless {
+                let _ = fs::remove_file(&path);
+                None
+            } else {
+                Some(format!("{}: no lesson with this name", path.display()))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,30 @@
+use learning_rust::structs::Rectangle;
+
+#[test]
+fn larger_can_hold_smaller() {
+    let larger = Rectangle {
+        width: 8,
+        height: 7,
+    };
+    let smaller = Rectangle {
+        width: 5,
+        height: 1,
+    };
+
+    assert!(larger.can_hold(&smaller));
+    assert!(!smaller.can_hold(&larger));
+}
+
+#[test]
+fn area_and_max() {
+    let square = Rectangle::square(3);
+    let wide = Rectangle {
+        width: 10,
+        height: 1,
+    };
+
+    assert_eq!(9, square.area());
+
+    let max = square.max(wide);
+    assert_eq!((10, 3), (max.width, max.height));
+}